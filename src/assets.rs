@@ -0,0 +1,407 @@
+use macroquad::experimental::animation::{AnimatedSprite, Animation};
+use macroquad::experimental::coroutines::{start_coroutine, Coroutine};
+use macroquad::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+
+use crate::config::{Config, Section};
+use crate::loading::LoadingScreen;
+use crate::wav::Wav;
+
+pub const ASSETS_FOLDER: &str = "assets";
+
+//missing files are swapped for placeholders and assets are hot reloaded
+//instead of only being read once at launch
+pub fn dev_mode() -> bool {
+    cfg!(debug_assertions)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AssetKind {
+    Texture,
+    Image,
+    Sound,
+    Font,
+    Data,
+}
+
+impl AssetKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            AssetKind::Texture => "texture",
+            AssetKind::Image => "image",
+            AssetKind::Sound => "sound",
+            AssetKind::Font => "font",
+            AssetKind::Data => "data",
+        }
+    }
+
+    fn from_section(name: &str) -> Option<Self> {
+        match name {
+            "textures" => Some(AssetKind::Texture),
+            "images" => Some(AssetKind::Image),
+            //music tracks are plain sounds to the loader
+            "sounds" | "music" => Some(AssetKind::Sound),
+            "fonts" => Some(AssetKind::Font),
+            "data" => Some(AssetKind::Data),
+            _ => None,
+        }
+    }
+}
+
+pub struct AssetError {
+    pub file: String,
+    pub reason: String,
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.file, self.reason)
+    }
+}
+
+//frame size and named rows of a sprite sheet, from a `[sprite <name>]` section
+pub struct SpriteSheet {
+    tile_width: u32,
+    tile_height: u32,
+    animations: Vec<Animation>,
+}
+
+impl SpriteSheet {
+    fn parse(section: &Section) -> Result<SpriteSheet, String> {
+        let frame = section.get("frame").ok_or("missing `frame = width, height`")?;
+        let (w, h) = frame.split_once(',').ok_or("`frame` needs a width and height")?;
+        let tile_width = w.trim().parse().map_err(|_| format!("bad frame width `{w}`"))?;
+        let tile_height = h.trim().parse().map_err(|_| format!("bad frame height `{h}`"))?;
+
+        let mut animations = vec![];
+        for line in section.get_all("animation") {
+            let parts: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [name, row, frames, fps] = parts[..] else {
+                return Err(format!("`animation = {line}` needs name, row, frames, fps"));
+            };
+            let number = |v: &str| v.parse().map_err(|_| format!("bad number `{v}` in `{line}`"));
+            animations.push(Animation {
+                name: name.to_string(),
+                row: number(row)?,
+                frames: number(frames)?,
+                fps: number(fps)?,
+            });
+        }
+        if animations.is_empty() {
+            return Err("no `animation` entries".to_string());
+        }
+
+        Ok(SpriteSheet {
+            tile_width,
+            tile_height,
+            animations,
+        })
+    }
+}
+
+struct Pending {
+    kind: AssetKind,
+    name: String,
+    file: String,
+    bytes: Coroutine<Result<Vec<u8>, macroquad::Error>>,
+}
+
+pub struct Assets {
+    //every manifest entry as (kind, name, file), kept for reloading
+    files: Vec<(AssetKind, String, String)>,
+    textures: HashMap<String, Texture2D>,
+    images: HashMap<String, Image>,
+    sounds: HashMap<String, Vec<u8>>,
+    fonts: HashMap<String, Vec<u8>>,
+    data: HashMap<String, String>,
+    sprites: HashMap<String, SpriteSheet>,
+    silence: Vec<u8>,
+}
+
+impl Assets {
+    //loads every file in the manifest at once and collects all failures
+    //rather than stopping at the first one
+    pub async fn load(
+        manifest: &str,
+        loading: &mut LoadingScreen<'_>,
+    ) -> Result<Assets, Vec<AssetError>> {
+        let config = match load_string(manifest).await {
+            Ok(text) => Config::parse(&text),
+            Err(e) => {
+                return Err(vec![AssetError {
+                    file: manifest.to_string(),
+                    reason: e.to_string(),
+                }]);
+            }
+        };
+
+        let mut errors = vec![];
+        let mut pending = vec![];
+        let mut sprites = HashMap::new();
+        for section in &config.sections {
+            if let Some(name) = section.name.strip_prefix("sprite ") {
+                match SpriteSheet::parse(section) {
+                    Ok(sheet) => {
+                        sprites.insert(name.trim().to_string(), sheet);
+                    }
+                    Err(reason) => errors.push(AssetError {
+                        file: manifest.to_string(),
+                        reason: format!("[{}] {reason}", section.name),
+                    }),
+                }
+                continue;
+            }
+            let Some(kind) = AssetKind::from_section(&section.name) else {
+                continue;
+            };
+            for (name, file) in &section.entries {
+                let path = file.clone();
+                pending.push(Pending {
+                    kind,
+                    name: name.clone(),
+                    file: file.clone(),
+                    bytes: start_coroutine(async move { load_file(&path).await }),
+                });
+            }
+        }
+
+        loading.expect(pending.len());
+        let mut done = 0;
+        loop {
+            let now_done = pending.iter().filter(|p| p.bytes.is_done()).count();
+            loading.finish(now_done - done);
+            done = now_done;
+            if done == pending.len() {
+                break;
+            }
+            loading.frame().await;
+        }
+
+        let mut assets = Assets {
+            files: pending
+                .iter()
+                .map(|p| (p.kind, p.name.clone(), p.file.clone()))
+                .collect(),
+            textures: HashMap::new(),
+            images: HashMap::new(),
+            sounds: HashMap::new(),
+            fonts: HashMap::new(),
+            data: HashMap::new(),
+            sprites,
+            silence: Wav {
+                channels: 1,
+                sample_rate: 44100,
+                samples: vec![0; 4410],
+            }
+            .to_bytes(),
+        };
+        for p in pending {
+            let loaded = p
+                .bytes
+                .retrieve()
+                .unwrap_or(Err(macroquad::Error::UnknownError("loading was cancelled")))
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| assets.insert(p.kind, &p.name, bytes));
+            if let Err(reason) = loaded {
+                if dev_mode() {
+                    warn!("{}: {}, using a placeholder", p.file, reason);
+                    assets.insert_placeholder(p.kind, &p.name);
+                }
+                errors.push(AssetError {
+                    file: p.file,
+                    reason,
+                });
+            }
+        }
+
+        if errors.is_empty() || dev_mode() {
+            Ok(assets)
+        } else {
+            Err(errors)
+        }
+    }
+
+    //decodes what can be checked up front so a corrupt file is reported
+    //here rather than panicking later
+    fn insert(&mut self, kind: AssetKind, name: &str, bytes: Vec<u8>) -> Result<(), String> {
+        let name = name.to_string();
+        match kind {
+            AssetKind::Texture => {
+                let image = Image::from_file_with_format(&bytes, None).map_err(|e| e.to_string())?;
+                let texture = Texture2D::from_image(&image);
+                //everything in this game is pixel art
+                texture.set_filter(FilterMode::Nearest);
+                self.textures.insert(name, texture);
+            }
+            AssetKind::Image => {
+                let image = Image::from_file_with_format(&bytes, None).map_err(|e| e.to_string())?;
+                self.images.insert(name, image);
+            }
+            AssetKind::Sound => {
+                //the decoder panics on anything it can't read, so only wav
+                //and ogg are let through
+                match bytes.get(0..4) {
+                    Some(b"RIFF") => Wav::check(&bytes)?,
+                    Some(b"OggS") => {}
+                    _ => return Err("not a wav or ogg file".to_string()),
+                }
+                self.sounds.insert(name, bytes);
+            }
+            AssetKind::Font => {
+                load_ttf_font_from_bytes(&bytes).map_err(|e| e.to_string())?;
+                self.fonts.insert(name, bytes);
+            }
+            AssetKind::Data => {
+                let text = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+                self.data.insert(name, text);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = (AssetKind, &str, String)> {
+        self.files
+            .iter()
+            .map(|(kind, name, file)| (*kind, name.as_str(), format!("{ASSETS_FOLDER}/{file}")))
+    }
+
+    //rereads a manifest entry from disk; textures are updated in place so every
+    //handle already given out picks up the change
+    pub fn reload(&mut self, kind: AssetKind, name: &str) -> Result<(), String> {
+        let Some((_, _, file)) = self.files.iter().find(|(k, n, _)| *k == kind && n == name) else {
+            return Err(format!("`{name}` is not in the manifest"));
+        };
+        let bytes = fs::read(format!("{ASSETS_FOLDER}/{file}")).map_err(|e| e.to_string())?;
+
+        if kind == AssetKind::Texture
+            && let Some(texture) = self.textures.get(name)
+        {
+            let image = Image::from_file_with_format(&bytes, None).map_err(|e| e.to_string())?;
+            if texture.size() != vec2(image.width as f32, image.height as f32) {
+                return Err(format!(
+                    "{file} changed size to {}x{}, restart to pick it up",
+                    image.width, image.height
+                ));
+            }
+            texture.update(&image);
+            return Ok(());
+        }
+        self.insert(kind, name, bytes)
+    }
+
+    //magenta checkers, silence and empty data stand in for missing files;
+    //fonts fall back to the ui's built in one
+    fn insert_placeholder(&mut self, kind: AssetKind, name: &str) {
+        let name = name.to_string();
+        match kind {
+            AssetKind::Texture => {
+                let texture = Texture2D::from_image(&placeholder_image());
+                texture.set_filter(FilterMode::Nearest);
+                self.textures.insert(name, texture);
+            }
+            AssetKind::Image => {
+                self.images.insert(name, placeholder_image());
+            }
+            AssetKind::Sound => {
+                self.sounds.insert(name, self.silence.clone());
+            }
+            AssetKind::Font => {}
+            AssetKind::Data => {
+                self.data.insert(name, String::new());
+            }
+        }
+    }
+
+    pub fn texture(&self, name: &str) -> Texture2D {
+        self.textures.get(name).cloned().unwrap_or_else(|| {
+            warn!("texture `{}` is not in the manifest", name);
+            Texture2D::from_image(&placeholder_image())
+        })
+    }
+
+    pub fn image(&self, name: &str) -> Image {
+        self.images.get(name).cloned().unwrap_or_else(|| {
+            warn!("image `{}` is not in the manifest", name);
+            placeholder_image()
+        })
+    }
+
+    pub fn sound(&self, name: &str) -> &[u8] {
+        self.sounds.get(name).unwrap_or_else(|| {
+            warn!("sound `{}` is not in the manifest", name);
+            &self.silence
+        })
+    }
+
+    pub fn font(&self, name: &str) -> Option<&[u8]> {
+        self.fonts.get(name).map(Vec::as_slice)
+    }
+
+    pub fn data(&self, name: &str) -> &str {
+        self.data.get(name).map_or("", String::as_str)
+    }
+
+    pub fn sprite(&self, name: &str) -> AnimatedSprite {
+        match self.sprites.get(name) {
+            Some(sheet) => AnimatedSprite::new(
+                sheet.tile_width,
+                sheet.tile_height,
+                &sheet.animations,
+                true,
+            ),
+            None => {
+                warn!("sprite `{}` is not in the manifest", name);
+                AnimatedSprite::new(
+                    16,
+                    16,
+                    &[Animation {
+                        name: name.to_string(),
+                        row: 0,
+                        frames: 1,
+                        fps: 1,
+                    }],
+                    true,
+                )
+            }
+        }
+    }
+}
+
+fn placeholder_image() -> Image {
+    let mut image = Image::gen_image_color(16, 16, MAGENTA);
+    for y in 0..16 {
+        for x in 0..16 {
+            if (x / 4 + y / 4) % 2 == 0 {
+                image.set_pixel(x, y, BLACK);
+            }
+        }
+    }
+    image
+}
+
+//blocking report for release builds, lists every failure until the window is closed
+pub async fn show_errors(errors: &[AssetError]) {
+    for error in errors {
+        error!("{}", error);
+    }
+    loop {
+        clear_background(BLACK);
+        draw_text("Couldn't load the game's assets:", 20.0, 40.0, 30.0, RED);
+        for (i, error) in errors.iter().enumerate() {
+            draw_text(error.to_string(), 20.0, 80.0 + i as f32 * 24.0, 20.0, WHITE);
+        }
+        draw_text(
+            "Press Escape to quit",
+            20.0,
+            screen_height() - 20.0,
+            20.0,
+            GRAY,
+        );
+        if is_key_pressed(KeyCode::Escape) {
+            std::process::exit(1);
+        }
+        next_frame().await
+    }
+}
@@ -0,0 +1,215 @@
+use macroquad::audio::{load_sound_from_bytes, play_sound, PlaySoundParams, Sound};
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+use crate::settings::Settings;
+use crate::viewport;
+use crate::wav::Wav;
+
+//pitch offsets baked for every wav effect, picked at random on each play
+const PITCH_VARIANTS: [f32; 3] = [0.94, 1.0, 1.06];
+//stereo positions baked for every wav effect, the closest one is played
+const PAN_VARIANTS: [f32; 5] = [-1.0, -0.5, 0.0, 0.5, 1.0];
+//seconds to fade everything out or back in when the window is left
+const AWAY_FADE: f32 = 0.5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Bus {
+    Music,
+    Sfx,
+}
+
+//a value that eases towards a target at a fixed rate per second
+#[derive(Clone, Copy)]
+pub struct Fade {
+    pub value: f32,
+    pub target: f32,
+    rate: f32,
+}
+
+impl Fade {
+    pub fn new(value: f32) -> Self {
+        Fade {
+            value,
+            target: value,
+            rate: 0.0,
+        }
+    }
+
+    pub fn to(&mut self, target: f32, seconds: f32) {
+        self.target = target;
+        self.rate = if seconds > 0.0 {
+            (target - self.value).abs() / seconds
+        } else {
+            f32::INFINITY
+        };
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        let step = self.rate * delta_time;
+        if (self.target - self.value).abs() <= step {
+            self.value = self.target;
+        } else {
+            self.value += step.copysign(self.target - self.value);
+        }
+    }
+}
+
+//every pitch and pan variant of a wav, indexed by pitch, then pan
+async fn bake(wav: &Wav) -> Result<Vec<Vec<Sound>>, macroquad::Error> {
+    let mut variants = vec![];
+    for pitch in PITCH_VARIANTS {
+        let pitched = wav.resampled(pitch);
+        let mut pans = vec![];
+        for pan in PAN_VARIANTS {
+            pans.push(load_sound_from_bytes(&pitched.panned(pan).to_bytes()).await?);
+        }
+        variants.push(pans);
+    }
+    Ok(variants)
+}
+
+//maps an x position on the playfield to a stereo pan
+pub fn screen_pan(x: f32) -> f32 {
+    (x / viewport::WIDTH * 2.0 - 1.0).clamp(-1.0, 1.0)
+}
+
+pub struct SoundEffect {
+    //indexed by pitch, then pan
+    variants: Vec<Vec<Sound>>,
+    duration: f32,
+    bus: Bus,
+    pub volume: f32,
+    pub volume_jitter: f32,
+    pub max_voices: usize,
+    //how hard the music ducks while this effect plays
+    pub duck: f32,
+    voices: Vec<f64>,
+}
+
+pub struct AudioManager {
+    master: f32,
+    buses: [f32; 2],
+    effects: HashMap<String, SoundEffect>,
+    duck: Fade,
+    //everything fades out while the game is auto paused in the background
+    away: Fade,
+}
+
+impl AudioManager {
+    pub fn new(settings: &Settings) -> Self {
+        let mut audio = AudioManager {
+            master: 1.0,
+            buses: [1.0; 2],
+            effects: HashMap::new(),
+            duck: Fade::new(0.0),
+            away: Fade::new(1.0),
+        };
+        audio.apply_settings(settings);
+        audio
+    }
+
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        self.set_master_volume(settings.master_volume);
+        self.set_bus_volume(Bus::Music, settings.music_volume);
+        self.set_bus_volume(Bus::Sfx, settings.sfx_volume);
+    }
+
+    pub fn set_bus_volume(&mut self, bus: Bus, volume: f32) {
+        self.buses[bus as usize] = volume.clamp(0.0, 1.0);
+    }
+
+    pub fn set_master_volume(&mut self, volume: f32) {
+        self.master = volume.clamp(0.0, 1.0);
+    }
+
+    pub fn set_away(&mut self, away: bool) {
+        let target = if away { 0.0 } else { 1.0 };
+        if self.away.target != target {
+            self.away.to(target, AWAY_FADE);
+        }
+    }
+
+    pub fn has_effect(&self, name: &str) -> bool {
+        self.effects.contains_key(name)
+    }
+
+    //decode the effect once and bake its pitch and pan variants, falling
+    //back to the raw sound for anything that isn't 16-bit pcm
+    pub async fn load_effect(&mut self, name: &str, bytes: &[u8], bus: Bus) -> &mut SoundEffect {
+        let (variants, duration) = match Wav::parse(bytes) {
+            Some(wav) => (bake(&wav).await, wav.duration()),
+            None => (load_sound_from_bytes(bytes).await.map(|s| vec![vec![s]]), 1.0),
+        };
+        //an effect that failed to load stays silent
+        let variants = variants.unwrap_or_else(|e| {
+            error!("sound effect `{}`: {}", name, e);
+            vec![]
+        });
+
+        //reloading keeps whatever tuning the effect was given after its first load
+        let effect = self
+            .effects
+            .entry(name.to_string())
+            .or_insert_with(|| SoundEffect {
+                variants: vec![],
+                duration,
+                bus,
+                volume: 1.0,
+                volume_jitter: 0.15,
+                max_voices: 4,
+                duck: 0.0,
+                voices: vec![],
+            });
+        effect.variants = variants;
+        effect.duration = duration;
+        effect
+    }
+
+    //pan runs from -1.0 (left) to 1.0 (right), volume scales the effect's own
+    pub fn play(&mut self, name: &str, pan: f32, volume: f32) {
+        let Some(effect) = self.effects.get_mut(name) else {
+            return;
+        };
+
+        //drop the new voice rather than stack more copies than the limit
+        let now = get_time();
+        effect.voices.retain(|end| *end > now);
+        if effect.voices.len() >= effect.max_voices {
+            return;
+        }
+
+        if effect.variants.is_empty() {
+            return;
+        }
+        let pitches = &effect.variants[rand::gen_range(0, effect.variants.len())];
+        let last = (pitches.len() - 1) as f32;
+        let step = ((pan.clamp(-1.0, 1.0) + 1.0) / 2.0 * last).round() as usize;
+        let jitter = rand::gen_range(1.0 - effect.volume_jitter, 1.0);
+        let volume =
+            volume * effect.volume * jitter * self.buses[effect.bus as usize] * self.master * self.away.value;
+        play_sound(
+            &pitches[step],
+            PlaySoundParams {
+                looped: false,
+                volume,
+            },
+        );
+        effect.voices.push(now + effect.duration as f64);
+
+        if effect.duck > self.duck.value {
+            self.duck = Fade::new(effect.duck);
+            self.duck.to(0.0, effect.duration);
+        }
+    }
+
+    //what the music controller multiplies its levels by, including ducking
+    pub fn music_gain(&self) -> f32 {
+        (1.0 - self.duck.value) * self.buses[Bus::Music as usize] * self.master * self.away.value
+    }
+
+    pub fn update(&mut self) {
+        self.duck.update(get_frame_time());
+        self.away.update(get_frame_time());
+    }
+}
@@ -0,0 +1,114 @@
+use macroquad::prelude::*;
+
+use crate::assets::Assets;
+use crate::stages::{LayerDesc, Stage};
+use crate::viewport;
+
+const STAGE_FADE: f32 = 2.0;
+//layer art is drawn at 2x like the sprites
+const LAYER_SCALE: f32 = 2.0;
+
+struct Layer {
+    texture: Texture2D,
+    speed: f32,
+    parallax: f32,
+    color: Color,
+    scroll: f32,
+    alpha: f32,
+    fading_out: bool,
+}
+
+//tiled image layers drawn over the starfield shader, swapped with a
+//crossfade whenever the run reaches a new stage
+pub struct Background {
+    layers: Vec<Layer>,
+    stage: Option<usize>,
+}
+
+impl Background {
+    pub fn new() -> Self {
+        Background {
+            layers: vec![],
+            stage: None,
+        }
+    }
+
+    //returns whether the stage changed
+    pub fn set_stage(&mut self, index: usize, stage: &Stage, assets: &Assets) -> bool {
+        if self.stage == Some(index) {
+            return false;
+        }
+        let first = self.stage.is_none();
+        self.stage = Some(index);
+
+        for layer in &mut self.layers {
+            layer.fading_out = true;
+        }
+        for LayerDesc {
+            texture,
+            speed,
+            parallax,
+            color,
+        } in &stage.layers
+        {
+            self.layers.push(Layer {
+                texture: assets.texture(texture),
+                speed: *speed,
+                parallax: *parallax,
+                color: *color,
+                scroll: 0.0,
+                alpha: if first { 1.0 } else { 0.0 },
+                fading_out: false,
+            });
+        }
+        true
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        let step = delta_time / STAGE_FADE;
+        for layer in &mut self.layers {
+            layer.scroll += layer.speed * delta_time;
+            layer.alpha = if layer.fading_out {
+                (layer.alpha - step).max(0.0)
+            } else {
+                (layer.alpha + step).min(1.0)
+            };
+        }
+        self.layers.retain(|l| !(l.fading_out && l.alpha == 0.0));
+    }
+
+    //direction_modifier is the same value fed to the starfield shader, in
+    //units of the playfield's height
+    pub fn draw(&self, direction_modifier: f32) {
+        for layer in &self.layers {
+            let tile = layer.texture.size() * LAYER_SCALE;
+            let offset = vec2(
+                (-direction_modifier * layer.parallax * viewport::HEIGHT).rem_euclid(tile.x),
+                layer.scroll.rem_euclid(tile.y),
+            );
+            let color = Color {
+                a: layer.color.a * layer.alpha,
+                ..layer.color
+            };
+
+            let mut y = offset.y - tile.y;
+            while y < viewport::HEIGHT {
+                let mut x = offset.x - tile.x;
+                while x < viewport::WIDTH {
+                    draw_texture_ex(
+                        &layer.texture,
+                        x,
+                        y,
+                        color,
+                        DrawTextureParams {
+                            dest_size: Some(tile),
+                            ..Default::default()
+                        },
+                    );
+                    x += tile.x;
+                }
+                y += tile.y;
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use macroquad::color::Color;
+use std::fs;
+
+//tiny `key = value` format shared by the game's data files, with optional
+//`[section]` headers and `#` comment lines. comments take the whole line so
+//values can hold `#rrggbb` colours
+pub struct Section {
+    pub name: String,
+    pub entries: Vec<(String, String)>,
+}
+
+impl Section {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    //every value for a repeated key, in file order
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+        self.entries
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn get_f32(&self, key: &str, default: f32) -> f32 {
+        self.get(key)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default)
+    }
+
+    pub fn get_u32(&self, key: &str, default: u32) -> u32 {
+        self.get(key)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default)
+    }
+
+    pub fn get_bool(&self, key: &str, default: bool) -> bool {
+        self.get(key)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default)
+    }
+}
+
+pub struct Config {
+    pub sections: Vec<Section>,
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Config {
+        //entries before the first header land in an unnamed root section
+        let mut sections = vec![Section {
+            name: String::new(),
+            entries: vec![],
+        }];
+        for line in text.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                sections.push(Section {
+                    name: name.trim().to_string(),
+                    entries: vec![],
+                });
+            } else if let Some((key, value)) = line.split_once('=') {
+                let section = sections.last_mut().unwrap();
+                section
+                    .entries
+                    .push((key.trim().to_string(), value.trim().to_string()));
+            }
+        }
+        Config { sections }
+    }
+
+    pub fn load(path: &str) -> Option<Config> {
+        fs::read_to_string(path).ok().map(|text| Config::parse(&text))
+    }
+
+    pub fn root(&self) -> &Section {
+        &self.sections[0]
+    }
+}
+
+//`#rrggbb` or `#rrggbbaa`
+pub fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Color::from_rgba(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sections_and_comments() {
+        let config = Config::parse(
+            "# header comment\n\
+             volume = 0.5\n\
+             \t  # indented comment = 1\n\
+             [stage one]\n\
+             layer = dust, 90, 1.5, #ffffff80\n\
+             layer = nebula, 12, 0.3\n\
+             stars_tint = #4a3a33\n\
+             empty =\n",
+        );
+        assert_eq!(config.sections.len(), 2);
+        assert_eq!(config.root().entries.len(), 1);
+        assert_eq!(config.root().get_f32("volume", 1.0), 0.5);
+        let stage = &config.sections[1];
+        assert_eq!(stage.name, "stage one");
+        let layers: Vec<&str> = stage.get_all("layer").collect();
+        assert_eq!(layers, ["dust, 90, 1.5, #ffffff80", "nebula, 12, 0.3"]);
+        assert_eq!(stage.get("stars_tint"), Some("#4a3a33"));
+        assert_eq!(stage.get("empty"), Some(""));
+        assert_eq!(stage.get("missing"), None);
+    }
+
+    #[test]
+    fn parses_colors() {
+        let color = parse_color("#ff800040").unwrap();
+        assert_eq!(
+            (color.r, color.g, color.b, color.a),
+            (1.0, 128.0 / 255.0, 0.0, 64.0 / 255.0)
+        );
+        assert_eq!(parse_color(" #000000 ").map(|c| c.a), Some(1.0));
+        assert!(parse_color("ffffff").is_none());
+        assert!(parse_color("#fff").is_none());
+        assert!(parse_color("#gggggg").is_none());
+    }
+}
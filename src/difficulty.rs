@@ -0,0 +1,158 @@
+//how hard a run is, picked on the main menu
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Preset {
+    Easy,
+    Normal,
+    Hard,
+    Insane,
+}
+
+//multipliers over the normal game
+pub struct Scale {
+    //times the per-frame chance of an enemy appearing
+    pub spawn: f32,
+    pub enemy_speed: f32,
+    //hits an enemy takes to go down
+    pub enemy_health: u32,
+    //the ship's fire cooldown, above 1.0 thins out its bullets
+    pub cooldown: f32,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [Preset::Easy, Preset::Normal, Preset::Hard, Preset::Insane];
+
+    //settings value, and string key in the languages
+    pub fn key(self) -> &'static str {
+        match self {
+            Preset::Easy => "easy",
+            Preset::Normal => "normal",
+            Preset::Hard => "hard",
+            Preset::Insane => "insane",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Preset> {
+        Preset::ALL.into_iter().find(|p| p.key() == value)
+    }
+
+    pub fn next(self) -> Preset {
+        Preset::ALL[(self as usize + 1) % Preset::ALL.len()]
+    }
+
+    pub fn scale(self) -> Scale {
+        let (spawn, enemy_speed, enemy_health, cooldown) = match self {
+            Preset::Easy => (0.6, 0.75, 1, 0.8),
+            Preset::Normal => (1.0, 1.0, 1, 1.0),
+            Preset::Hard => (1.4, 1.25, 2, 1.15),
+            Preset::Insane => (2.0, 1.5, 3, 1.3),
+        };
+        Scale {
+            spawn,
+            enemy_speed,
+            enemy_health,
+            cooldown,
+        }
+    }
+}
+
+//the preset plus whether the director leans on it, which is what high
+//scores are kept under
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Difficulty {
+    pub preset: Preset,
+    pub dynamic: bool,
+}
+
+impl Difficulty {
+    pub fn key(self) -> String {
+        if self.dynamic {
+            format!("{}_dynamic", self.preset.key())
+        } else {
+            self.preset.key().to_string()
+        }
+    }
+}
+
+//seconds the recent kill count is averaged over
+const RECENT: f32 = 8.0;
+//kills per second a player keeping up with normal pressure manages
+const EXPECTED_KILL_RATE: f32 = 0.8;
+const MIN_PRESSURE: f32 = 0.6;
+const MAX_PRESSURE: f32 = 1.6;
+//pressure change per second, slow enough not to be noticed
+const PRESSURE_RATE: f32 = 0.05;
+
+//dynamic difficulty: spawns more and faster enemies while the player is
+//clearing them quickly, and backs off while they struggle
+pub struct Director {
+    enabled: bool,
+    //decays over RECENT seconds
+    recent_kills: f32,
+    pressure: f32,
+}
+
+impl Director {
+    pub fn new(enabled: bool) -> Self {
+        Director {
+            enabled,
+            //starts as if the player was keeping up
+            recent_kills: EXPECTED_KILL_RATE * RECENT,
+            pressure: 1.0,
+        }
+    }
+
+    pub fn kill(&mut self) {
+        self.recent_kills += 1.0;
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        if !self.enabled {
+            return;
+        }
+        self.recent_kills *= (-delta_time / RECENT).exp();
+        let rate = self.recent_kills / RECENT;
+        let target = (rate / EXPECTED_KILL_RATE).clamp(MIN_PRESSURE, MAX_PRESSURE);
+        let step = PRESSURE_RATE * delta_time;
+        self.pressure += (target - self.pressure).clamp(-step, step);
+    }
+
+    //multiplies spawn rate and enemy speed, 1.0 is the preset as it is
+    pub fn pressure(&self) -> f32 {
+        self.pressure
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_round_trip_and_cycle() {
+        for preset in Preset::ALL {
+            assert_eq!(Preset::parse(preset.key()), Some(preset));
+        }
+        assert_eq!(Preset::Insane.next(), Preset::Easy);
+        let difficulty = Difficulty {
+            preset: Preset::Hard,
+            dynamic: true,
+        };
+        assert_eq!(difficulty.key(), "hard_dynamic");
+    }
+
+    #[test]
+    fn director_follows_the_kill_rate() {
+        let mut fixed = Director::new(false);
+        let mut idle = Director::new(true);
+        let mut busy = Director::new(true);
+        for _ in 0..600 {
+            fixed.update(0.1);
+            idle.update(0.1);
+            busy.kill();
+            busy.kill();
+            busy.update(0.1);
+        }
+        assert_eq!(fixed.pressure(), 1.0);
+        assert_eq!(idle.pressure(), MIN_PRESSURE);
+        assert_eq!(busy.pressure(), MAX_PRESSURE);
+    }
+}
@@ -0,0 +1,280 @@
+use macroquad::prelude::*;
+use macroquad_particles::{AtlasConfig, BlendMode, ColorCurve, Curve, Emitter, EmitterConfig};
+use std::collections::HashMap;
+
+use crate::assets::Assets;
+use crate::config::{parse_color, Config, Section};
+
+//one-shot effects get this many emitters each unless they set `pool`
+const DEFAULT_POOL: usize = 6;
+
+struct Preset {
+    config: EmitterConfig,
+    //emitters kept for this effect, also how many can play at once
+    pool: usize,
+}
+
+//named particle presets, from the `[effect <name>]` sections of the effects
+//data file. enemy and weapon definitions refer to them by name
+pub struct Effects {
+    presets: HashMap<String, Preset>,
+}
+
+//comma separated numbers, anything unparsable is dropped
+fn numbers(value: &str) -> Vec<f32> {
+    value.split(',').filter_map(|v| v.trim().parse().ok()).collect()
+}
+
+//`start, mid, end` colours over the particle's life
+pub fn color_curve(value: &str) -> Option<ColorCurve> {
+    let colors: Option<Vec<Color>> = value.split(',').map(parse_color).collect();
+    match colors?[..] {
+        [start, mid, end] => Some(ColorCurve { start, mid, end }),
+        _ => None,
+    }
+}
+
+//everything but the texture, which needs the assets
+fn preset(section: &Section) -> EmitterConfig {
+    let mut config = EmitterConfig {
+        local_coords: false,
+        one_shot: section.get_bool("one_shot", false),
+        amount: section.get_u32("amount", 8),
+        explosiveness: section.get_f32("explosiveness", 0.0),
+        ..Default::default()
+    };
+    //`key = value[, randomness]`
+    let pair = |key: &str, value: &mut f32, randomness: &mut f32| {
+        if let Some(v) = section.get(key) {
+            let v = numbers(v);
+            *value = v.first().copied().unwrap_or(*value);
+            *randomness = v.get(1).copied().unwrap_or(0.0);
+        }
+    };
+    pair("lifetime", &mut config.lifetime, &mut config.lifetime_randomness);
+    pair(
+        "velocity",
+        &mut config.initial_velocity,
+        &mut config.initial_velocity_randomness,
+    );
+    pair("size", &mut config.size, &mut config.size_randomness);
+
+    if let [x, y] = numbers(section.get("direction").unwrap_or(""))[..] {
+        config.initial_direction = vec2(x, y);
+    }
+    config.initial_direction_spread = section.get_f32("spread", 0.0).to_radians();
+    if let [x, y] = numbers(section.get("gravity").unwrap_or(""))[..] {
+        config.gravity = vec2(x, y);
+    }
+    //`time:scale` points over the particle's life
+    if let Some(points) = section.get("size_curve") {
+        config.size_curve = Some(Curve {
+            points: points
+                .split(',')
+                .filter_map(|p| {
+                    let (t, v) = p.split_once(':')?;
+                    Some((t.trim().parse().ok()?, v.trim().parse().ok()?))
+                })
+                .collect(),
+            ..Default::default()
+        });
+    }
+    if let Some(colors) = section.get("colors") {
+        match color_curve(colors) {
+            Some(curve) => config.colors_curve = curve,
+            None => warn!("effect {}: `colors` needs start, mid, end", section.name),
+        }
+    }
+    //`columns, rows[, first frame, frame count]`
+    if let Some(atlas) = section.get("atlas") {
+        let v: Vec<u16> = atlas.split(',').filter_map(|v| v.trim().parse().ok()).collect();
+        config.atlas = match v[..] {
+            [n, m] => Some(AtlasConfig::new(n, m, ..)),
+            [n, m, first, count] => Some(AtlasConfig::new(n, m, first..first + count)),
+            _ => {
+                warn!("effect {}: bad `atlas = {}`", section.name, atlas);
+                None
+            }
+        };
+    }
+    config.blend_mode = match section.get("blend") {
+        Some("additive") => BlendMode::Additive,
+        _ => BlendMode::Alpha,
+    };
+    config
+}
+
+impl Effects {
+    pub fn load(text: &str, assets: &Assets) -> Effects {
+        let config = Config::parse(text);
+        let presets = config
+            .sections
+            .iter()
+            .filter_map(|section| {
+                let name = section.name.strip_prefix("effect ")?;
+                let mut config = preset(section);
+                if let Some(texture) = section.get("texture") {
+                    config.texture = Some(assets.texture(texture));
+                }
+                let pool = if config.one_shot {
+                    section.get_u32("pool", DEFAULT_POOL as u32).max(1) as usize
+                } else {
+                    0
+                };
+                Some((name.trim().to_string(), Preset { config, pool }))
+            })
+            .collect();
+        Effects { presets }
+    }
+
+    pub fn config(&self, name: &str) -> EmitterConfig {
+        self.presets.get(name).map(|p| p.config.clone()).unwrap_or_else(|| {
+            warn!("particle effect `{}` is not defined", name);
+            EmitterConfig::default()
+        })
+    }
+
+    //`scale` multiplies the particle count and speed, so bigger things can
+    //share a preset with smaller ones
+    fn scaled(&self, name: &str, scale: f32) -> EmitterConfig {
+        let config = self.config(name);
+        EmitterConfig {
+            amount: (config.amount as f32 * scale).round().max(1.0) as u32,
+            initial_velocity: config.initial_velocity * scale,
+            ..config
+        }
+    }
+}
+
+struct Slot {
+    emitter: Emitter,
+    position: Vec2,
+    age: f32,
+    //seconds until the last particle of the current burst has died
+    busy_for: f32,
+}
+
+impl Slot {
+    fn busy(&self) -> bool {
+        self.age < self.busy_for
+    }
+}
+
+//a fixed set of emitters per one-shot effect, created up front since every
+//emitter compiles its own pipeline. when an effect is already playing as many
+//times as it has emitters the oldest copy makes way for the new one
+pub struct EmitterPool {
+    pools: HashMap<String, Vec<Slot>>,
+}
+
+impl EmitterPool {
+    pub fn new(effects: &Effects) -> Self {
+        let pools = effects
+            .presets
+            .iter()
+            .filter(|(_, preset)| preset.pool > 0)
+            .map(|(name, preset)| {
+                let slots = (0..preset.pool)
+                    .map(|_| Slot {
+                        emitter: Emitter::new(EmitterConfig {
+                            emitting: false,
+                            ..preset.config.clone()
+                        }),
+                        position: Vec2::ZERO,
+                        age: 0.0,
+                        busy_for: 0.0,
+                    })
+                    .collect();
+                (name.clone(), slots)
+            })
+            .collect();
+        EmitterPool { pools }
+    }
+
+    //plays a one-shot effect, `scale` as for `Effects::scaled`
+    pub fn spawn(&mut self, effects: &Effects, name: &str, position: Vec2, scale: f32) {
+        let Some(slots) = self.pools.get_mut(name) else {
+            warn!("particle effect `{}` is not a pooled one-shot", name);
+            return;
+        };
+        let config = effects.scaled(name, scale);
+        let slot = match slots.iter().position(|s| !s.busy()) {
+            Some(i) => &mut slots[i],
+            //an emitter can't be reset from outside, so the oldest is rebuilt
+            //and its particles vanish. that costs a pipeline and buffers that
+            //are never freed, so `pool` should cover the usual peak
+            None => {
+                let oldest = slots
+                    .iter_mut()
+                    .max_by(|a, b| a.age.total_cmp(&b.age))
+                    .expect("pools have at least one slot");
+                oldest.emitter = Emitter::new(config.clone());
+                oldest
+            }
+        };
+        slot.busy_for = config.lifetime * (2.0 - config.explosiveness);
+        slot.emitter.config = EmitterConfig {
+            emitting: true,
+            ..config
+        };
+        slot.position = position;
+        slot.age = 0.0;
+    }
+
+    //hides everything, for a fresh run. the particles still have to run out
+    //before their emitter is free again, so they're made invisible instead
+    pub fn clear(&mut self) {
+        for slot in self.pools.values_mut().flatten() {
+            slot.emitter.config.emitting = false;
+            slot.emitter.config.colors_curve = ColorCurve {
+                start: BLANK,
+                mid: BLANK,
+                end: BLANK,
+            };
+        }
+    }
+
+    //on real time like the emitters themselves, which read the frame time and
+    //can't be handed a slower one, so effects ignore the game speed and hit-stop
+    pub fn draw(&mut self) {
+        let delta_time = get_frame_time();
+        for slot in self.pools.values_mut().flatten().filter(|s| s.busy()) {
+            slot.age += delta_time;
+            slot.emitter.draw(slot.position);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_color_curves() {
+        let curve = color_curve("#ffffff, #80c0ff, #2040ff00").unwrap();
+        assert_eq!(curve.start, WHITE);
+        assert_eq!(curve.end.a, 0.0);
+        assert!(color_curve("#ffffff, #80c0ff").is_none());
+        assert!(color_curve("#ffffff, blue, #000000").is_none());
+    }
+
+    #[test]
+    fn parses_presets() {
+        let config = Config::parse(
+            "[effect spark]\none_shot = true\nlifetime = 0.5, 0.2\ncolors = #ffffff, #ff8000, #ff000000\n\
+             [effect smoke]\ncolors = #ffffff, grey, #000000\n\
+             [effect bare]\n",
+        );
+        let section = |name: &str| preset(config.sections.iter().find(|s| s.name == name).unwrap());
+        let spark = section("effect spark");
+        assert!(spark.one_shot);
+        assert_eq!((spark.lifetime, spark.lifetime_randomness), (0.5, 0.2));
+        assert_eq!(spark.colors_curve.end.a, 0.0);
+        //a bad colour keeps the default curve rather than part of the new one
+        assert_eq!(section("effect smoke").colors_curve, ColorCurve::default());
+        let bare = section("effect bare");
+        assert!(!bare.one_shot);
+        assert_eq!(bare.amount, 8);
+        assert_eq!(bare.colors_curve, ColorCurve::default());
+    }
+}
@@ -0,0 +1,50 @@
+use crate::config::Config;
+
+pub struct EnemyDef {
+    pub name: String,
+    //largest enemy drawn as this type, types are checked smallest first
+    pub max_size: f32,
+    pub texture: String,
+    pub sprite: String,
+    //particle effect played when it is destroyed
+    pub explosion: String,
+}
+
+//`[enemy <name>]` sections, sorted by size
+pub fn load(text: &str) -> Vec<EnemyDef> {
+    let config = Config::parse(text);
+    let mut enemies: Vec<EnemyDef> = config
+        .sections
+        .iter()
+        .filter_map(|section| {
+            let name = section.name.strip_prefix("enemy ")?.trim();
+            let get = |key: &str| section.get(key).unwrap_or(name).to_string();
+            Some(EnemyDef {
+                name: name.to_string(),
+                max_size: section.get_f32("max_size", f32::MAX),
+                texture: get("texture"),
+                sprite: get("sprite"),
+                explosion: section.get("explosion").unwrap_or("explosion").to_string(),
+            })
+        })
+        .collect();
+    enemies.sort_by(|a, b| a.max_size.total_cmp(&b.max_size));
+
+    if enemies.is_empty() {
+        enemies.push(EnemyDef {
+            name: "enemy".to_string(),
+            max_size: f32::MAX,
+            texture: "enemy_small".to_string(),
+            sprite: "enemy_small".to_string(),
+            explosion: "explosion".to_string(),
+        });
+    }
+    enemies
+}
+
+pub fn for_size(enemies: &[EnemyDef], size: f32) -> usize {
+    enemies
+        .iter()
+        .position(|e| size <= e.max_size)
+        .unwrap_or(enemies.len() - 1)
+}
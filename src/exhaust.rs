@@ -0,0 +1,141 @@
+use macroquad::prelude::*;
+use macroquad_particles::{ColorCurve, Emitter, EmitterConfig};
+
+use crate::effects::Effects;
+
+//how quickly the plume follows the controls, per second
+const RESPONSE: f32 = 8.0;
+//seconds the engine sputters after the ship is hit
+const DAMAGE_TIME: f32 = 2.0;
+//sideways tilt of the plume and shift of its source at full bank
+const BANK_TILT: f32 = 0.35;
+const BANK_OFFSET: f32 = 4.0;
+
+//the ship's engine plume, built from the `exhaust` effect and bent around
+//by the controls: thrusting forward stretches and brightens it, braking
+//shrinks it and banking pushes it to the side. the boost and damage colours
+//come from the `exhaust_boost` and `exhaust_damaged` effects
+pub struct Exhaust {
+    emitter: Emitter,
+    base: EmitterConfig,
+    boost_colors: ColorCurve,
+    damaged_colors: ColorCurve,
+    thrust: f32,
+    bank: f32,
+    boost: f32,
+    damaged: f32,
+}
+
+fn mix(a: Color, b: Color, t: f32) -> Color {
+    Color::from_vec(a.to_vec().lerp(b.to_vec(), t))
+}
+
+fn mix_curve(a: &ColorCurve, b: &ColorCurve, t: f32) -> ColorCurve {
+    ColorCurve {
+        start: mix(a.start, b.start, t),
+        mid: mix(a.mid, b.mid, t),
+        end: mix(a.end, b.end, t),
+    }
+}
+
+fn brighten(color: Color, amount: f32) -> Color {
+    Color {
+        r: (color.r * amount).min(1.0),
+        g: (color.g * amount).min(1.0),
+        b: (color.b * amount).min(1.0),
+        ..color
+    }
+}
+
+//base colours pulled towards the boost and damage ones, then lit up by the
+//engine's power. the end colour is left alone so the trail still fades out
+fn plume_colors(
+    base: &ColorCurve,
+    boost: &ColorCurve,
+    damaged: &ColorCurve,
+    boosting: f32,
+    damage: f32,
+    power: f32,
+) -> ColorCurve {
+    let colors = mix_curve(base, boost, boosting);
+    let colors = mix_curve(&colors, damaged, damage);
+    ColorCurve {
+        start: brighten(colors.start, power),
+        mid: brighten(colors.mid, power),
+        end: colors.end,
+    }
+}
+
+impl Exhaust {
+    pub fn new(effects: &Effects) -> Self {
+        let base = effects.config("exhaust");
+        Exhaust {
+            emitter: Emitter::new(base.clone()),
+            boost_colors: effects.config("exhaust_boost").colors_curve,
+            damaged_colors: effects.config("exhaust_damaged").colors_curve,
+            base,
+            thrust: 0.0,
+            bank: 0.0,
+            boost: 0.0,
+            damaged: 0.0,
+        }
+    }
+
+    pub fn hit(&mut self) {
+        self.damaged = DAMAGE_TIME;
+    }
+
+    //`thrust` is -1.0 braking to 1.0 full forward, `bank` -1.0 left to 1.0 right
+    pub fn update(&mut self, delta_time: f32, thrust: f32, bank: f32, boosting: bool) {
+        let follow = (delta_time * RESPONSE).min(1.0);
+        self.thrust += (thrust - self.thrust) * follow;
+        self.bank += (bank - self.bank) * follow;
+        self.boost += (if boosting { 1.0 } else { 0.0 } - self.boost) * follow;
+        self.damaged = (self.damaged - delta_time).max(0.0);
+
+        let power = (1.0 + 0.5 * self.thrust) * (1.0 + 0.4 * self.boost);
+        let damage = self.damaged / DAMAGE_TIME;
+
+        let config = &mut self.emitter.config;
+        config.lifetime = self.base.lifetime * power;
+        config.initial_velocity = self.base.initial_velocity * power;
+        config.size = self.base.size * (0.75 + 0.25 * power);
+        //particles trail away from the way the ship is banking
+        config.initial_direction = vec2(self.bank * BANK_TILT, -1.0).normalize();
+        config.colors_curve = plume_colors(
+            &self.base.colors_curve,
+            &self.boost_colors,
+            &self.damaged_colors,
+            self.boost,
+            damage,
+            power,
+        );
+        //a damaged engine coughs instead of burning steadily
+        config.emitting = damage == 0.0 || rand::gen_range(0.0, 1.0) > damage * 0.6;
+    }
+
+    pub fn draw(&mut self, position: Vec2) {
+        self.emitter.draw(position - vec2(self.bank * BANK_OFFSET, 0.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::color_curve;
+
+    #[test]
+    fn boost_and_damage_take_over_the_colours() {
+        let base = color_curve("#404040, #808080, #000000").unwrap();
+        let boost = color_curve("#2040ff, #80c0ff, #000000").unwrap();
+        let damaged = color_curve("#ff2000, #ff8000, #00000000").unwrap();
+        assert_eq!(plume_colors(&base, &boost, &damaged, 0.0, 0.0, 1.0), base);
+        assert_eq!(plume_colors(&base, &boost, &damaged, 1.0, 0.0, 1.0), boost);
+        assert_eq!(plume_colors(&base, &boost, &damaged, 1.0, 1.0, 1.0), damaged);
+        //more power brightens up to white but leaves the fade out alone
+        let bright = plume_colors(&base, &boost, &damaged, 1.0, 0.0, 2.0);
+        assert_eq!(bright.mid.b, 1.0);
+        assert!(bright.start.r > boost.start.r);
+        assert_eq!(bright.end, boost.end);
+    }
+}
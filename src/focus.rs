@@ -0,0 +1,48 @@
+use macroquad::input::utils::{register_input_subscriber, repeat_all_miniquad_input};
+use macroquad::miniquad::EventHandler;
+
+//whether the window has the platform's focus, from the minimize and restore
+//events miniquad passes on. on x11, windows, macos and the web those also fire
+//when another window takes focus, so alt-tab counts. wayland sends neither,
+//so there the game is only paused by hand. read once a frame before the
+//states update
+pub struct WindowFocus {
+    subscriber: usize,
+    focused: bool,
+    //focus went away since the last frame, even if it is already back
+    left: bool,
+}
+
+impl WindowFocus {
+    pub fn new() -> Self {
+        WindowFocus {
+            subscriber: register_input_subscriber(),
+            focused: true,
+            left: false,
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.left = false;
+        repeat_all_miniquad_input(self, self.subscriber);
+    }
+
+    pub fn lost(&self) -> bool {
+        !self.focused || self.left
+    }
+}
+
+impl EventHandler for WindowFocus {
+    fn update(&mut self) {}
+
+    fn draw(&mut self) {}
+
+    fn window_minimized_event(&mut self) {
+        self.focused = false;
+        self.left = true;
+    }
+
+    fn window_restored_event(&mut self) {
+        self.focused = true;
+    }
+}
@@ -0,0 +1,124 @@
+use macroquad::experimental::animation::AnimatedSprite;
+use macroquad::prelude::*;
+use macroquad::ui::{root_ui, Skin};
+
+use crate::assets::Assets;
+use crate::audio::AudioManager;
+use crate::background::Background;
+use crate::effects::{Effects, EmitterPool};
+use crate::enemies::{self, EnemyDef};
+use crate::exhaust::Exhaust;
+use crate::focus::WindowFocus;
+use crate::gamepad::Gamepad;
+use crate::hud::Hud;
+use crate::impact::Impacts;
+use crate::lang::Locale;
+use crate::music::MusicController;
+use crate::outline::Outline;
+use crate::postfx::PostFx;
+use crate::settings::Settings;
+use crate::skin;
+use crate::stages::Stage;
+use crate::starfield::Starfield;
+use crate::stats::{HighScores, Lifetime};
+use crate::weapons::WeaponDef;
+use crate::wipe::Wipe;
+
+//everything that outlives a single state, handed to whichever states are
+//on the stack
+pub struct Game {
+    pub settings: Settings,
+    pub assets: Assets,
+    pub audio: AudioManager,
+    pub music: MusicController,
+    pub starfield: Starfield,
+    pub background: Background,
+    pub stages: Vec<Stage>,
+    pub postfx: PostFx,
+    pub impacts: Impacts,
+    pub wipe: Wipe,
+    pub outline: Outline,
+    pub hud: Hud,
+    //buttons with keyboard or pad focus, see nav.rs
+    pub focus_skin: Skin,
+    pub gamepad: Gamepad,
+    pub window_focus: WindowFocus,
+    pub locale: Locale,
+    pub effects: Effects,
+    pub effect_pool: EmitterPool,
+    pub exhaust: Exhaust,
+    pub enemy_defs: Vec<EnemyDef>,
+    pub enemy_textures: Vec<Texture2D>,
+    pub enemy_sprites: Vec<AnimatedSprite>,
+    pub weapon_defs: Vec<WeaponDef>,
+    pub ship_texture: Texture2D,
+    pub ship_sprite: AnimatedSprite,
+    pub bullet_texture: Texture2D,
+    pub bullet_sprite: AnimatedSprite,
+    //score of the run in progress, which also picks the stage
+    pub score: u32,
+    //best for the difficulty being played
+    pub high_score: u32,
+    pub high_scores: HighScores,
+    pub lifetime: Lifetime,
+}
+
+impl Game {
+    pub fn load_enemies(&mut self) {
+        self.enemy_defs = enemies::load(self.assets.data("enemies"));
+        self.enemy_textures = self
+            .enemy_defs
+            .iter()
+            .map(|e| self.assets.texture(&e.texture))
+            .collect();
+        self.enemy_sprites = self
+            .enemy_defs
+            .iter()
+            .map(|e| self.assets.sprite(&e.sprite))
+            .collect();
+    }
+
+    //after a ui image or font is reloaded or the language changes, languages
+    //the ui font can't draw get the default font
+    pub fn rebuild_skin(&mut self) {
+        let font = self.locale.language_fits_ui_font();
+        let ui_skin = skin::build(&self.assets, font);
+        root_ui().pop_skin();
+        root_ui().push_skin(&ui_skin);
+        self.focus_skin = skin::build_focus(&self.assets, font, &ui_skin);
+    }
+
+    //everything that reads the settings while playing
+    pub fn apply_settings(&mut self) {
+        self.audio.apply_settings(&self.settings);
+        self.postfx.apply_settings(&self.settings);
+        self.impacts.apply_settings(&self.settings);
+        self.starfield.apply_settings(&self.settings);
+    }
+
+    pub fn set_language(&mut self, code: &str) {
+        if self.locale.set_language(code) {
+            self.settings.language = code.to_string();
+            self.rebuild_skin();
+        }
+    }
+
+    pub fn load_effects(&mut self) {
+        self.effects = Effects::load(self.assets.data("effects"), &self.assets);
+        self.exhaust = Exhaust::new(&self.effects);
+        self.effect_pool = EmitterPool::new(&self.effects);
+    }
+}
+
+//top left corner that centers a ui window of `size` in the window
+pub fn centered(size: Vec2) -> Vec2 {
+    vec2(
+        screen_width() / 2.0 - size.x / 2.0,
+        screen_height() / 2.0 - size.y / 2.0,
+    )
+}
+
+//darkens the playfield under a menu, in window space
+pub fn dim(rect: Rect) {
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0.0, 0.0, 0.0, 0.6));
+}
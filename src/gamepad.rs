@@ -0,0 +1,38 @@
+use gilrs::{Button, EventType, Gilrs};
+use macroquad::prelude::*;
+
+//buttons pressed on any connected pad, since macroquad has no gamepad input
+//of its own. read once a frame before the states update
+pub struct Gamepad {
+    //left empty when the platform's pad support fails to start
+    gilrs: Option<Gilrs>,
+    pressed: Vec<Button>,
+}
+
+impl Gamepad {
+    pub fn new() -> Self {
+        Gamepad {
+            gilrs: Gilrs::new()
+                .inspect_err(|e| warn!("gamepads unavailable: {}", e))
+                .ok(),
+            pressed: vec![],
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.pressed.clear();
+        let Some(gilrs) = &mut self.gilrs else {
+            return;
+        };
+        while let Some(event) = gilrs.next_event() {
+            if let EventType::ButtonPressed(button, _) = event.event {
+                self.pressed.push(button);
+            }
+        }
+    }
+
+    //went down this frame
+    pub fn pressed(&self, button: Button) -> bool {
+        self.pressed.contains(&button)
+    }
+}
@@ -0,0 +1,132 @@
+use macroquad::prelude::*;
+use std::fs;
+use std::time::SystemTime;
+
+use crate::assets::{AssetKind, Assets};
+use crate::shader::ShaderFile;
+
+const POLL_INTERVAL: f64 = 0.5;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Watched {
+    Asset(AssetKind, String),
+    Shader(&'static str),
+}
+
+struct WatchedFile {
+    path: String,
+    modified: Option<SystemTime>,
+    watched: Watched,
+}
+
+//polls modification times of the manifest's files and the shader sources,
+//and keeps the latest failure per asset, or per group of shaders compiled
+//together, for the on-screen overlay
+pub struct HotReload {
+    files: Vec<WatchedFile>,
+    shaders: Vec<Vec<&'static ShaderFile>>,
+    next_poll: f64,
+    errors: Vec<(String, String)>,
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl HotReload {
+    //`shaders` lists the files of each shader owner, which are all
+    //recompiled when any of them changes
+    pub fn new(assets: &Assets, shaders: Vec<Vec<&'static ShaderFile>>) -> Self {
+        let mut files = vec![];
+        for (kind, name, path) in assets.files() {
+            files.push(WatchedFile {
+                modified: modified(&path),
+                path,
+                watched: Watched::Asset(kind, name.to_string()),
+            });
+        }
+        for shader in shaders.iter().flatten() {
+            files.push(WatchedFile {
+                path: shader.path.to_string(),
+                modified: modified(shader.path),
+                watched: Watched::Shader(shader.path),
+            });
+        }
+
+        HotReload {
+            files,
+            shaders,
+            next_poll: 0.0,
+            errors: vec![],
+        }
+    }
+
+    pub fn poll(&mut self) -> Vec<Watched> {
+        let now = get_time();
+        if now < self.next_poll {
+            return vec![];
+        }
+        self.next_poll = now + POLL_INTERVAL;
+
+        let mut changed = vec![];
+        for file in &mut self.files {
+            let modified = modified(&file.path);
+            if modified != file.modified {
+                file.modified = modified;
+                if !changed.contains(&file.watched) {
+                    changed.push(file.watched.clone());
+                }
+            }
+        }
+        changed
+    }
+
+    //the same name can be in several sections of the manifest, and a reload
+    //of any file of a shader group replaces the whole group's result
+    fn key(&self, watched: &Watched) -> String {
+        match watched {
+            Watched::Asset(kind, name) => format!("{} {}", kind.name(), name),
+            Watched::Shader(path) => self
+                .shaders
+                .iter()
+                .find(|group| group.iter().any(|f| f.path == *path))
+                .map_or(*path, |group| group[0].path)
+                .to_string(),
+        }
+    }
+
+    pub fn report(&mut self, watched: &Watched, result: Result<(), String>) {
+        let key = self.key(watched);
+        self.errors.retain(|(k, _)| *k != key);
+        match result {
+            Ok(()) => info!("reloaded {}", key),
+            Err(e) => {
+                error!("{}: {}", key, e);
+                self.errors.push((key, e));
+            }
+        }
+    }
+
+    pub fn draw_overlay(&self) {
+        if self.errors.is_empty() {
+            return;
+        }
+        let lines: Vec<String> = self
+            .errors
+            .iter()
+            .flat_map(|(key, e)| {
+                std::iter::once(format!("{key}:")).chain(e.lines().map(|l| format!("  {l}")))
+            })
+            .collect();
+        draw_rectangle(
+            0.0,
+            0.0,
+            screen_width(),
+            lines.len() as f32 * 18.0 + 16.0,
+            Color::new(0.0, 0.0, 0.0, 0.8),
+        );
+        for (i, line) in lines.iter().enumerate() {
+            draw_text(line, 8.0, 20.0 + i as f32 * 18.0, 18.0, RED);
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use macroquad::prelude::*;
+
+use crate::assets::Assets;
+use crate::config::Section;
+use crate::lang::Locale;
+use crate::viewport::{self, Viewport};
+
+//everything below is in playfield units and scaled with the window
+const MARGIN: f32 = 16.0;
+//between items stacked on the same anchor
+const SPACING: f32 = 6.0;
+const FONT_SIZE: f32 = 28.0;
+const BAR_HEIGHT: f32 = 8.0;
+const COMBO_BAR_WIDTH: f32 = 160.0;
+const COMBO_COLOR: Color = Color::new(1.0, 0.8, 0.25, 1.0);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Element {
+    Score,
+    HighScore,
+    Stage,
+    Combo,
+    Weapon,
+    Lives,
+}
+
+impl Element {
+    //also the order items are stacked in when they share an anchor
+    pub const ALL: [Element; 6] = [
+        Element::Score,
+        Element::HighScore,
+        Element::Stage,
+        Element::Combo,
+        Element::Weapon,
+        Element::Lives,
+    ];
+
+    //settings key after the `hud_` prefix, and string key in the languages
+    pub fn key(self) -> &'static str {
+        match self {
+            Element::Score => "score",
+            Element::HighScore => "high_score",
+            Element::Stage => "stage",
+            Element::Combo => "combo",
+            Element::Weapon => "weapon",
+            Element::Lives => "lives",
+        }
+    }
+}
+
+//corner or edge of the playfield an item is pinned to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    pub const ALL: [Anchor; 6] = [
+        Anchor::TopLeft,
+        Anchor::Top,
+        Anchor::TopRight,
+        Anchor::BottomLeft,
+        Anchor::Bottom,
+        Anchor::BottomRight,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Anchor::TopLeft => "top_left",
+            Anchor::Top => "top",
+            Anchor::TopRight => "top_right",
+            Anchor::BottomLeft => "bottom_left",
+            Anchor::Bottom => "bottom",
+            Anchor::BottomRight => "bottom_right",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Anchor> {
+        Anchor::ALL.into_iter().find(|a| a.key() == value)
+    }
+
+    fn bottom(self) -> bool {
+        matches!(self, Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Placement {
+    pub anchor: Anchor,
+    //nudge away from the anchor's spot, in playfield units
+    pub offset: Vec2,
+}
+
+//where each element goes, None hides it
+#[derive(Clone, Debug)]
+pub struct HudLayout {
+    placements: [Option<Placement>; Element::ALL.len()],
+}
+
+impl Default for HudLayout {
+    fn default() -> Self {
+        let at = |anchor| {
+            Some(Placement {
+                anchor,
+                offset: Vec2::ZERO,
+            })
+        };
+        let mut placements = [None; Element::ALL.len()];
+        for element in Element::ALL {
+            placements[element as usize] = match element {
+                Element::Score | Element::Combo => at(Anchor::TopLeft),
+                Element::HighScore => at(Anchor::TopRight),
+                Element::Stage => at(Anchor::Top),
+                Element::Weapon => at(Anchor::BottomLeft),
+                Element::Lives => at(Anchor::BottomRight),
+            };
+        }
+        HudLayout { placements }
+    }
+}
+
+impl HudLayout {
+    //`hud_<element> = <anchor>[, x, y]` or `off`, unset keys keep the default
+    pub fn load(section: &Section) -> Self {
+        let mut layout = HudLayout::default();
+        for element in Element::ALL {
+            let key = format!("hud_{}", element.key());
+            let Some(value) = section.get(&key) else {
+                continue;
+            };
+            let parts: Vec<&str> = value.split(',').map(str::trim).collect();
+            if parts[0] == "off" {
+                layout.placements[element as usize] = None;
+                continue;
+            }
+            let Some(anchor) = Anchor::parse(parts[0]) else {
+                warn!("{}: unknown anchor `{}`", key, parts[0]);
+                continue;
+            };
+            let number = |i: usize| parts.get(i).and_then(|v| v.parse().ok()).unwrap_or(0.0);
+            layout.placements[element as usize] = Some(Placement {
+                anchor,
+                offset: vec2(number(1), number(2)),
+            });
+        }
+        layout
+    }
+
+    pub fn to_config(&self) -> String {
+        let mut text = String::new();
+        for element in Element::ALL {
+            let value = match self.get(element) {
+                None => "off".to_string(),
+                Some(p) if p.offset == Vec2::ZERO => p.anchor.key().to_string(),
+                Some(p) => format!("{}, {}, {}", p.anchor.key(), p.offset.x, p.offset.y),
+            };
+            text.push_str(&format!("hud_{} = {}\n", element.key(), value));
+        }
+        text
+    }
+
+    pub fn get(&self, element: Element) -> Option<Placement> {
+        self.placements[element as usize]
+    }
+
+    //keeps any offset the element already had
+    pub fn set_anchor(&mut self, element: Element, anchor: Option<Anchor>) {
+        let placement = &mut self.placements[element as usize];
+        *placement = anchor.map(|anchor| Placement {
+            anchor,
+            offset: placement.map_or(Vec2::ZERO, |p| p.offset),
+        });
+    }
+}
+
+//what the hud shows this frame
+pub struct HudData<'a> {
+    pub score: u32,
+    pub high_score: u32,
+    pub stage: &'a str,
+    pub combo: u32,
+    //share of the combo window left before it drops
+    pub combo_left: f32,
+    pub weapon: &'a str,
+    pub lives: u32,
+}
+
+enum Item {
+    Text(String),
+    //label over a bar filled to the fraction
+    Meter(String, f32, f32, Color),
+}
+
+pub struct Hud {
+    //the default font stands in when the ui font isn't there
+    font: Option<Font>,
+}
+
+impl Hud {
+    pub fn new(assets: &Assets) -> Self {
+        Hud {
+            font: assets
+                .font("ui")
+                .and_then(|bytes| load_ttf_font_from_bytes(bytes).ok()),
+        }
+    }
+
+    fn item(element: Element, data: &HudData, locale: &Locale) -> Option<Item> {
+        let key = format!("hud.{}", element.key());
+        let text = |name, value: &dyn std::fmt::Display| locale.format(&key, &[(name, value)]);
+        match element {
+            Element::Score => Some(Item::Text(text("score", &format!("{:06}", data.score)))),
+            Element::HighScore => Some(Item::Text(text("score", &format!("{:06}", data.high_score)))),
+            Element::Stage => Some(Item::Text(text("stage", &data.stage.to_uppercase()))),
+            //a single kill isn't a combo yet
+            Element::Combo => (data.combo > 1).then(|| {
+                Item::Meter(text("combo", &data.combo), data.combo_left, COMBO_BAR_WIDTH, COMBO_COLOR)
+            }),
+            Element::Weapon => Some(Item::Text(text("weapon", &data.weapon.to_uppercase()))),
+            Element::Lives => Some(Item::Text(text("lives", &data.lives))),
+        }
+    }
+
+    //in window space, items sharing an anchor stack away from the edge
+    pub fn draw(&self, layout: &HudLayout, data: &HudData, locale: &Locale) {
+        let playfield = Viewport::screen_rect();
+        let scale = playfield.h / viewport::HEIGHT;
+        let font_size = (FONT_SIZE * scale).round() as u16;
+        let mut stacked = [0.0; 6];
+
+        for element in Element::ALL {
+            let Some(placement) = layout.get(element) else {
+                continue;
+            };
+            let Some(item) = Self::item(element, data, locale) else {
+                continue;
+            };
+            let (label, bar) = match &item {
+                Item::Text(text) => (text, None),
+                Item::Meter(text, fill, width, color) => (text, Some((*fill, *width * scale, *color))),
+            };
+            //same rule as the ui skin, a language the ui font can't draw all
+            //of is drawn in the default font throughout
+            let font = self.font.as_ref().filter(|_| locale.language_fits_ui_font());
+            let text = measure_text(label, font, font_size, 1.0);
+            let bar_height = bar.map_or(0.0, |_| (SPACING + BAR_HEIGHT) * scale);
+            let size = vec2(
+                bar.map_or(text.width, |(_, width, _)| text.width.max(width)),
+                font_size as f32 + bar_height,
+            );
+
+            let anchor = placement.anchor;
+            let margin = MARGIN * scale;
+            let x = match anchor {
+                Anchor::TopLeft | Anchor::BottomLeft => playfield.x + margin,
+                Anchor::Top | Anchor::Bottom => playfield.center().x - size.x / 2.0,
+                Anchor::TopRight | Anchor::BottomRight => playfield.right() - margin - size.x,
+            };
+            let stack = &mut stacked[anchor as usize];
+            let y = if anchor.bottom() {
+                playfield.bottom() - margin - *stack - size.y
+            } else {
+                playfield.y + margin + *stack
+            };
+            *stack += size.y + SPACING * scale;
+            let pos = vec2(x, y) + placement.offset * scale;
+
+            //bars and text share the item's left edge, centred items centre both
+            let text_x = match anchor {
+                Anchor::Top | Anchor::Bottom => pos.x + (size.x - text.width) / 2.0,
+                _ => pos.x,
+            };
+            draw_text_ex(
+                label,
+                text_x,
+                pos.y + text.offset_y,
+                TextParams {
+                    font,
+                    font_size,
+                    color: WHITE,
+                    ..Default::default()
+                },
+            );
+            if let Some((fill, width, color)) = bar {
+                let bar_y = pos.y + font_size as f32 + SPACING * scale;
+                let height = BAR_HEIGHT * scale;
+                draw_rectangle(pos.x, bar_y, width * fill.clamp(0.0, 1.0), height, color);
+                draw_rectangle_lines(pos.x, bar_y, width, height, 2.0, WHITE);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn layout_round_trips_through_settings() {
+        let mut layout = HudLayout::default();
+        layout.set_anchor(Element::Score, Some(Anchor::Bottom));
+        layout.set_anchor(Element::Lives, None);
+        layout.placements[Element::Weapon as usize] = Some(Placement {
+            anchor: Anchor::TopRight,
+            offset: vec2(4.0, -2.5),
+        });
+
+        let loaded = HudLayout::load(Config::parse(&layout.to_config()).root());
+        for element in Element::ALL {
+            let (a, b) = (layout.get(element), loaded.get(element));
+            assert_eq!(a.map(|p| (p.anchor, p.offset)), b.map(|p| (p.anchor, p.offset)));
+        }
+    }
+
+    #[test]
+    fn bad_or_missing_keys_keep_the_default() {
+        let config = Config::parse("hud_score = middle\nhud_combo = off\nhud_stage = top_left, 8\n");
+        let layout = HudLayout::load(config.root());
+        let defaults = HudLayout::default();
+        assert_eq!(layout.get(Element::Score).map(|p| p.anchor), Some(Anchor::TopLeft));
+        assert!(layout.get(Element::Combo).is_none());
+        let stage = layout.get(Element::Stage).unwrap();
+        assert_eq!((stage.anchor, stage.offset), (Anchor::TopLeft, vec2(8.0, 0.0)));
+        assert_eq!(
+            layout.get(Element::Weapon).map(|p| p.anchor),
+            defaults.get(Element::Weapon).map(|p| p.anchor)
+        );
+    }
+}
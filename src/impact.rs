@@ -0,0 +1,120 @@
+use macroquad::prelude::*;
+
+use crate::settings::Settings;
+use crate::viewport::Viewport;
+
+//playfield units and degrees the view moves at full trauma
+const MAX_OFFSET: f32 = 18.0;
+const MAX_ROTATION: f32 = 1.5;
+//trauma lost per second
+const TRAUMA_DECAY: f32 = 1.4;
+const SHAKE_FREQUENCY: f32 = 22.0;
+const FLASH_DECAY: f32 = 4.0;
+
+//collisions worth feeling
+pub enum Impact {
+    Kill { size: f32 },
+    Damage,
+}
+
+//trauma based screen shake, hit-stop and full screen flashes. trauma adds up
+//from impacts and drains over time while the shake follows its square, so
+//small hits barely register and big ones ramp up quickly
+pub struct Impacts {
+    trauma: f32,
+    hit_stop: f32,
+    flash: Color,
+    time: f32,
+    shake_intensity: f32,
+    flash_intensity: f32,
+}
+
+impl Impacts {
+    pub fn new(settings: &Settings) -> Self {
+        let mut impacts = Impacts {
+            trauma: 0.0,
+            hit_stop: 0.0,
+            flash: Color::new(1.0, 1.0, 1.0, 0.0),
+            time: 0.0,
+            shake_intensity: 0.0,
+            flash_intensity: 0.0,
+        };
+        impacts.apply_settings(settings);
+        impacts
+    }
+
+    //reduced motion turns both off whatever the sliders say
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        let motion = if settings.reduced_motion { 0.0 } else { 1.0 };
+        self.shake_intensity = settings.shake_intensity * motion;
+        self.flash_intensity = settings.flash_intensity * motion;
+    }
+
+    pub fn trigger(&mut self, impact: Impact) {
+        match impact {
+            Impact::Kill { size } => {
+                let big = size > 48.0;
+                self.add_trauma(if big { 0.35 } else { 0.12 });
+                if big {
+                    self.stop(0.06);
+                    self.flash(Color::new(1.0, 1.0, 1.0, 0.2));
+                }
+            }
+            Impact::Damage => {
+                self.add_trauma(0.8);
+                self.stop(0.2);
+                self.flash(Color::new(1.0, 0.1, 0.1, 0.5));
+            }
+        }
+    }
+
+    fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.0);
+    }
+
+    fn stop(&mut self, seconds: f32) {
+        self.hit_stop = self.hit_stop.max(seconds);
+    }
+
+    fn flash(&mut self, color: Color) {
+        let alpha = color.a * self.flash_intensity;
+        if alpha >= self.flash.a {
+            self.flash = Color { a: alpha, ..color };
+        }
+    }
+
+    //runs on real time so the hit-stop can end
+    pub fn update(&mut self, delta_time: f32) {
+        self.hit_stop = (self.hit_stop - delta_time).max(0.0);
+        self.trauma = (self.trauma - TRAUMA_DECAY * delta_time).max(0.0);
+        self.flash.a = (self.flash.a - FLASH_DECAY * delta_time * self.flash.a.max(0.1)).max(0.0);
+        self.time += delta_time * SHAKE_FREQUENCY;
+    }
+
+    //multiplier for gameplay time, 0.0 while the action is frozen
+    pub fn time_scale(&self) -> f32 {
+        if self.hit_stop > 0.0 { 0.0 } else { 1.0 }
+    }
+
+    //camera offset in playfield units and rotation in degrees
+    pub fn shake(&self) -> (Vec2, f32) {
+        let shake = self.trauma * self.trauma * self.shake_intensity;
+        let offset = vec2(noise(self.time, 0.0), noise(self.time, 13.7)) * MAX_OFFSET * shake;
+        (offset, noise(self.time, 41.3) * MAX_ROTATION * shake)
+    }
+
+    //over the playfield, in screen space
+    pub fn draw_flash(&self) {
+        if self.flash.a > 0.0 {
+            let rect = Viewport::screen_rect();
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, self.flash);
+        }
+    }
+}
+
+//smooth wobble between -1.0 and 1.0, a few octaves of sines stand in for
+//proper noise
+fn noise(t: f32, seed: f32) -> f32 {
+    ((t + seed).sin() + (t * 2.3 + seed * 1.7).sin() * 0.5 + (t * 5.1 + seed * 2.9).sin() * 0.25)
+        / 1.75
+}
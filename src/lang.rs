@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::assets::{AssetKind, Assets};
+use crate::config::Config;
+
+//data assets named `lang_<code>` are the languages, english is where any
+//missing string falls back to
+const DATA_PREFIX: &str = "lang_";
+const FALLBACK: &str = "en";
+
+//which glyphs a font has, to spot text it can't draw
+struct Coverage(fontdue::Font);
+
+impl Coverage {
+    fn covers(&self, text: &str) -> bool {
+        text.chars()
+            .all(|c| c.is_whitespace() || self.0.lookup_glyph_index(c) != 0)
+    }
+}
+
+struct Language {
+    code: String,
+    name: String,
+    strings: HashMap<String, String>,
+    //counts that take the `.one` form of a plural
+    plural_one: Vec<u32>,
+    //between groups of three digits, empty for none
+    digit_separator: String,
+}
+
+impl Language {
+    fn parse(code: &str, text: &str) -> Self {
+        let config = Config::parse(text);
+        let root = config.root();
+        let strings = config
+            .sections
+            .iter()
+            .filter(|section| section.name == "strings")
+            .flat_map(|section| section.entries.iter().cloned())
+            .collect();
+        Language {
+            code: code.to_string(),
+            name: root.get("name").unwrap_or(code).to_string(),
+            strings,
+            plural_one: root
+                .get("plural_one")
+                .unwrap_or("1")
+                .split(',')
+                .filter_map(|n| n.trim().parse().ok())
+                .collect(),
+            //the config format trims values, so a space is spelled out
+            digit_separator: match root.get("digit_separator").unwrap_or("") {
+                "space" => " ".to_string(),
+                separator => separator.to_string(),
+            },
+        }
+    }
+}
+
+pub struct Locale {
+    languages: Vec<Language>,
+    current: usize,
+    fallback: usize,
+    //the ui font, None when it's missing and the default font is used anyway
+    coverage: Option<Coverage>,
+    //every string of the current language can be drawn with the ui font
+    fits: bool,
+}
+
+impl Locale {
+    pub fn load(assets: &Assets, code: &str) -> Self {
+        let mut languages: Vec<Language> = assets
+            .files()
+            .filter(|(kind, _, _)| *kind == AssetKind::Data)
+            .filter_map(|(_, name, _)| name.strip_prefix(DATA_PREFIX))
+            .map(|code| Language::parse(code, assets.data(&format!("{DATA_PREFIX}{code}"))))
+            .collect();
+        if languages.is_empty() {
+            languages.push(Language::parse(FALLBACK, ""));
+        }
+        let fallback = languages.iter().position(|l| l.code == FALLBACK).unwrap_or(0);
+        let coverage = assets
+            .font("ui")
+            .and_then(|bytes| fontdue::Font::from_bytes(bytes, Default::default()).ok())
+            .map(Coverage);
+
+        let mut locale = Locale {
+            languages,
+            current: fallback,
+            fallback,
+            coverage,
+            fits: true,
+        };
+        locale.set_language(code);
+        locale
+    }
+
+    //unknown codes leave the language as it was
+    pub fn set_language(&mut self, code: &str) -> bool {
+        let Some(index) = self.languages.iter().position(|l| l.code == code) else {
+            return false;
+        };
+        self.current = index;
+        self.fits = self.languages[index]
+            .strings
+            .values()
+            .all(|text| self.fits_ui_font(text));
+        true
+    }
+
+    pub fn code(&self) -> &str {
+        &self.languages[self.current].code
+    }
+
+    //code and display name of every language, in manifest order
+    pub fn languages(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.languages.iter().map(|l| (l.code.as_str(), l.name.as_str()))
+    }
+
+    //false when text needs glyphs the ui font doesn't have
+    fn fits_ui_font(&self, text: &str) -> bool {
+        self.coverage.as_ref().is_none_or(|c| c.covers(text))
+    }
+
+    //the same for the whole current language, which decides the font of the
+    //ui skin and the hud. a language that doesn't fit uses the default font
+    //everywhere rather than mixing the two
+    pub fn language_fits_ui_font(&self) -> bool {
+        self.fits
+    }
+
+    //missing strings fall back to english, then to the key itself
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        [self.current, self.fallback]
+            .iter()
+            .find_map(|&i| self.languages[i].strings.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    //the string for `key`, or `default` when no language has one
+    pub fn get_or<'a>(&'a self, key: &str, default: &'a str) -> &'a str {
+        [self.current, self.fallback]
+            .iter()
+            .find_map(|&i| self.languages[i].strings.get(key))
+            .map_or(default, String::as_str)
+    }
+
+    //`{name}` placeholders in the string are filled from `args`
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = self.get(key).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+        text
+    }
+
+    //`<key>.one` or `<key>.other` depending on `n`, which is also `{n}`
+    pub fn plural(&self, key: &str, n: u32, args: &[(&str, &dyn Display)]) -> String {
+        let form = if self.languages[self.current].plural_one.contains(&n) {
+            "one"
+        } else {
+            "other"
+        };
+        let number = self.number(n);
+        let mut args = args.to_vec();
+        args.push(("n", &number));
+        self.format(&format!("{key}.{form}"), &args)
+    }
+
+    //digits grouped the way the language writes them
+    pub fn number(&self, n: u32) -> String {
+        let separator = &self.languages[self.current].digit_separator;
+        let digits = n.to_string();
+        let mut text = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                text.push_str(separator);
+            }
+            text.push(c);
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locale(code: &str) -> Locale {
+        let languages = vec![
+            Language::parse("en", include_str!("../assets/lang-en.cfg")),
+            Language::parse("de", include_str!("../assets/lang-de.cfg")),
+        ];
+        let mut locale = Locale {
+            languages,
+            current: 0,
+            fallback: 0,
+            coverage: None,
+            fits: true,
+        };
+        assert!(locale.set_language(code));
+        locale
+    }
+
+    #[test]
+    fn falls_back_to_english_then_the_key() {
+        let mut locale = locale("de");
+        locale.languages[1].strings.remove("menu.play");
+        assert_eq!(locale.get("menu.play"), locale.languages[0].strings["menu.play"]);
+        assert_eq!(locale.get("no.such.key"), "no.such.key");
+        assert_eq!(locale.get_or("no.such.key", "x"), "x");
+        assert!(!locale.set_language("xx"));
+        assert_eq!(locale.code(), "de");
+    }
+
+    #[test]
+    fn plurals_and_numbers() {
+        let en = locale("en");
+        assert_eq!(en.number(0), "0");
+        assert_eq!(en.number(999), "999");
+        assert_eq!(en.number(1234567), "1,234,567");
+        assert_eq!(en.plural("results.runs", 1, &[]), "all time: 1 run");
+        assert_eq!(en.plural("results.runs", 2, &[]), "all time: 2 runs");
+
+        let de = locale("de");
+        assert_eq!(de.number(1234567), "1.234.567");
+        assert_eq!(de.plural("results.runs", 1200, &[]), "insgesamt: 1.200 Runden");
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        let en = locale("en");
+        let text = en.format("results.difficulty_dynamic", &[("difficulty", &"Hard")]);
+        assert!(text.contains("Hard") && !text.contains('{'));
+    }
+}
@@ -0,0 +1,75 @@
+use macroquad::prelude::*;
+
+use crate::starfield::Starfield;
+use crate::viewport::Viewport;
+
+//counts units of startup work so the bar keeps moving through file loads
+//as well as the slower sound decoding that follows them
+pub struct LoadingScreen<'a> {
+    starfield: &'a mut Starfield,
+    total: usize,
+    loaded: usize,
+    //later stages add work as they are discovered, the bar never moves back
+    shown: f32,
+    //what's being worked on, empty until the languages are loaded
+    label: String,
+}
+
+impl<'a> LoadingScreen<'a> {
+    pub fn new(starfield: &'a mut Starfield) -> Self {
+        LoadingScreen {
+            starfield,
+            total: 0,
+            loaded: 0,
+            shown: 0.0,
+            label: String::new(),
+        }
+    }
+
+    pub fn set_label(&mut self, label: &str) {
+        self.label = label.to_string();
+    }
+
+    pub fn expect(&mut self, steps: usize) {
+        self.total += steps;
+    }
+
+    pub fn finish(&mut self, steps: usize) {
+        self.loaded = (self.loaded + steps).min(self.total);
+    }
+
+    //draws one frame of the loading screen and yields to the engine
+    pub async fn frame(&mut self) {
+        self.starfield.update(get_frame_time());
+        self.starfield.render();
+        clear_background(BLACK);
+        self.starfield.draw(Viewport::screen_rect());
+
+        if self.total > 0 {
+            self.shown = self.shown.max(self.loaded as f32 / self.total as f32);
+        }
+        let progress = self.shown;
+        let width = screen_width() * 0.6;
+        let x = screen_width() / 2.0 - width / 2.0;
+        let y = screen_height() * 0.75;
+        draw_rectangle(x, y, width * progress, 16.0, WHITE);
+        draw_rectangle_lines(x - 4.0, y - 4.0, width + 8.0, 24.0, 2.0, WHITE);
+
+        let percent = (progress * 100.0).round();
+        let text = if self.label.is_empty() {
+            format!("{}%", percent)
+        } else {
+            format!("{} {}%", self.label, percent)
+        };
+        let text_dimensions = measure_text(&text, None, 25, 1.0);
+        draw_text(
+            &text,
+            screen_width() / 2.0 - text_dimensions.width / 2.0,
+            y - 20.0,
+            25.0,
+            WHITE,
+        );
+
+        next_frame().await
+    }
+}
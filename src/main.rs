@@ -1,659 +1,306 @@
 use macroquad::prelude::*;
-use std::fs;
-use macroquad_particles::{self as particles, AtlasConfig, ColorCurve, Emitter, EmitterConfig};
-use macroquad::experimental::animation::{AnimatedSprite, Animation};
-use macroquad::audio::{load_sound, play_sound, play_sound_once, set_sound_volume, PlaySoundParams};
-use macroquad::ui::{hash, root_ui, Skin};
-
-const FRAGMENT_SHADER: &str = include_str!("starfield-shader.glsl");
-
-const VERTEX_SHADER: &str = "#version 100
-attribute vec3 position;
-attribute vec2 texcoord;
-attribute vec4 color0;
-varying float iTime;
-
-uniform mat4 Model;
-uniform mat4 Projection;
-uniform vec4 _Time;
-
-void main() {
-    gl_Position = Projection * Model * vec4(position, 1);
-    iTime = _Time.x;
-}
-";
-
-struct Shape {
-    size: f32,
-    speed: f32,
-    x: f32,
-    y: f32,
-    collided: bool,
-}
-
-impl Shape {
-    fn collides_with(&self, other: &Self) -> bool {
-        self.rect().overlaps(&other.rect())
-    }
-
-    fn rect(&self) -> Rect {
-        Rect {
-            x: self.x - self.size / 2.0,
-            y: self.y - self.size / 2.0,
-            w: self.size,
-            h: self.size,
-        }
-    }
-}
-
-enum GameState {
-    MainMenu,
-    Playing,
-    Paused,
-    GameOver,
-}
-
-fn particle_explosion() -> particles::EmitterConfig {
-    particles::EmitterConfig {
-        local_coords: false,
-        one_shot: true,
-        emitting: true,
-        lifetime: 0.6,
-        lifetime_randomness: 0.3,
-        explosiveness: 0.65,
-        initial_direction_spread: 2.0 * std::f32::consts::PI,
-        //initial_velocity: 300.0,
-        initial_velocity_randomness: 0.8,
-        size: 16.0,
-        size_randomness: 0.3,
-        atlas: Some(AtlasConfig::new(5, 1, 0..)),
-        ..Default::default()
-    }
-}
-
-fn particle_exhaust() -> particles::EmitterConfig {
-    particles::EmitterConfig {
-        local_coords: false,
-        one_shot: false,
-        emitting: true,
-        lifetime: 0.6,
-        lifetime_randomness: 0.3,
-        explosiveness: 0.4,
-        initial_direction_spread: std::f32::consts::PI / 12.0,
-        initial_velocity: -500.0,
-        initial_velocity_randomness: 0.8,
-        size: 3.0,
-        size_randomness: 0.3,
-        colors_curve: ColorCurve {
-            start: DARKGRAY,
-            mid: WHITE,
-            end: BLACK,
-        },
+use macroquad::ui::root_ui;
+
+mod assets;
+mod audio;
+mod background;
+mod config;
+mod difficulty;
+mod effects;
+mod enemies;
+mod exhaust;
+mod focus;
+mod game;
+mod gamepad;
+mod hot_reload;
+mod hud;
+mod impact;
+mod lang;
+mod loading;
+mod music;
+mod nav;
+mod outline;
+mod palette;
+mod postfx;
+mod settings;
+mod shader;
+mod skin;
+mod stages;
+mod starfield;
+mod stats;
+mod state;
+mod states;
+mod viewport;
+mod wav;
+mod weapons;
+mod wipe;
+
+use assets::{dev_mode, AssetKind, Assets};
+use audio::{AudioManager, Bus};
+use background::Background;
+use effects::{EmitterPool, Effects};
+use exhaust::Exhaust;
+use focus::WindowFocus;
+use game::Game;
+use gamepad::Gamepad;
+use hot_reload::{HotReload, Watched};
+use hud::Hud;
+use impact::Impacts;
+use lang::Locale;
+use loading::LoadingScreen;
+use music::MusicController;
+use outline::Outline;
+use postfx::PostFx;
+use settings::Settings;
+use starfield::Starfield;
+use state::StateStack;
+use states::MainMenu;
+use stats::{HighScores, Lifetime};
+use viewport::Viewport;
+use wipe::Wipe;
+
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "MyGame".to_string(),
+        window_width: 1280,
+        window_height: 720,
         ..Default::default()
     }
 }
 
-#[macroquad::main("MyGame")]
+#[macroquad::main(window_conf)]
 async fn main() {
-    //seed RNG and set some useful consts
+    //seed RNG
     rand::srand(miniquad::date::now() as u64);
-    const MOVEMENT_SPEED: f32 = 200.0;
-    
-    //track enemies and bullets
-    let mut squares = vec![];
-    let mut bullets: Vec<Shape> = vec![];
-    let mut explosions: Vec<(Emitter, Vec2)> = vec![];
-
-    //set up the player shape
-    let mut circle = Shape {
-        size: 32.0,
-        speed: MOVEMENT_SPEED,
-        x: screen_width() / 2.0,
-        y: screen_height() / 2.0,
-        collided: false,
-    };
 
-    //create an exhaust stream for the player
-    let mut exhaust = Emitter::new(EmitterConfig {
-        amount: circle.size.round() as u32 * 2,
-            ..particle_exhaust()
-    });
+    let settings = Settings::load();
+    let mut starfield = Starfield::new(&settings);
 
-    let mut game_state = GameState::MainMenu;
-    let mut gitgud = false;
-    let mut last_shot = get_time();
-    let mut score: u32 = 0;
-    let mut high_score: u32 = fs::read_to_string("highscore.dat")
-        .map_or(Ok(0), |i| i.parse::<u32>())
-        .unwrap_or(0);
-
-    let mut direction_modifier: f32 = 0.0;
-    let render_target = render_target(320, 150);
-    render_target.texture.set_filter(FilterMode::Nearest);
-    let material = load_material(
-        ShaderSource::Glsl {
-            vertex: VERTEX_SHADER,
-            fragment: FRAGMENT_SHADER,
-        },
-        MaterialParams {
-            uniforms: vec![
-                UniformDesc::new("iResolution", UniformType::Float2),
-                UniformDesc::new("direction_modifier", UniformType::Float1),
-            ],
-            ..Default::default()
-        },
-    )
-    .unwrap();
-
-    //import textures
+    //load everything listed in the asset manifest
     set_pc_assets_folder("assets");
-    let ship_texture: Texture2D = load_texture("ship.png")
-        .await
-        .expect("Couldn't load file");
-    ship_texture.set_filter(FilterMode::Nearest);
-    let bullet_texture: Texture2D = load_texture("laser-bolts.png")
-        .await
-        .expect("Couldn't load file");
-    bullet_texture.set_filter(FilterMode::Nearest);
-    let explosion_texture: Texture2D = load_texture("explosion.png")
-        .await
-        .expect("Couldn't load file");
-    explosion_texture.set_filter(FilterMode::Nearest);
-
-    let enemy_small_texture: Texture2D = load_texture("enemy-small.png")
-        .await
-        .expect("Couldn't load file");
-    enemy_small_texture.set_filter(FilterMode::Nearest);
-    let enemy_medium_texture: Texture2D = load_texture("enemy-medium.png")
-        .await
-        .expect("Couldn't load file");
-    enemy_medium_texture.set_filter(FilterMode::Nearest);
-    let enemy_big_texture: Texture2D = load_texture("enemy-big.png")
-        .await
-        .expect("Couldn't load file");
-    enemy_big_texture.set_filter(FilterMode::Nearest);
-    build_textures_atlas();
+    let mut loading = LoadingScreen::new(&mut starfield);
+    let assets = match Assets::load("assets.cfg", &mut loading).await {
+        Ok(assets) => assets,
+        Err(errors) => {
+            assets::show_errors(&errors).await;
+            return;
+        }
+    };
+    //loaded first so the rest of the loading screen is in the player's language
+    let locale = Locale::load(&assets, &settings.language);
+    let ship_texture = assets.texture("ship");
+    let bullet_texture = assets.texture("bullet");
+    let effects = Effects::load(assets.data("effects"), &assets);
+    let effect_pool = EmitterPool::new(&effects);
+    let enemy_defs = enemies::load(assets.data("enemies"));
+    let enemy_textures: Vec<Texture2D> =
+        enemy_defs.iter().map(|e| assets.texture(&e.texture)).collect();
+    let weapon_defs = weapons::load(assets.data("weapons"));
+    let lifetime = Lifetime::load();
+    //the atlas keeps its own copy of each texture, which hot reloading can't update
+    if !dev_mode() {
+        build_textures_atlas();
+    }
 
     //music loading
-    let theme_music = load_sound("8bit-spaceshooter.ogg").await.unwrap();
-    let sound_explosion = load_sound("explosion.wav").await.unwrap();
-    let sound_laser = load_sound("laser.wav").await.unwrap();
-
-    //UI loading
-    let window_background = load_image("window_background.png").await.unwrap();
-    let button_background = load_image("button_background.png").await.unwrap();
-    let button_clicked_background = load_image("button_clicked_background.png").await.unwrap();
-    let font = load_file("atari_games.ttf").await.unwrap();
-
-    //bullet sprite config
-    let mut bullet_sprite = AnimatedSprite::new(
-        16,
-        16,
-        &[
-            Animation {
-                name: "bullet".to_string(),
-                row: 0,
-                frames: 2,
-                fps: 12,
-            },
-            Animation {
-                name: "bolt".to_string(),
-                row: 1,
-                frames: 2,
-                fps: 12,
-            },
-        ],
-        true,
-    );
+    let mut audio = AudioManager::new(&settings);
+    loading.set_label(locale.get("loading.music"));
+    let music = MusicController::load(assets.data("music"), &assets, &mut loading).await;
+    loading.expect(2);
+    loading.set_label(locale.get("loading.sounds"));
+    loading.frame().await;
+    let explosion = audio.load_effect("explosion", assets.sound("explosion"), Bus::Sfx).await;
+    explosion.max_voices = 6;
+    explosion.duck = 0.3;
+    loading.finish(1);
+    loading.frame().await;
+    let laser = audio.load_effect("laser", assets.sound("laser"), Bus::Sfx).await;
+    laser.volume = 0.7;
+    laser.max_voices = 2;
+    loading.finish(1);
+
+    //sprite configs
+    let mut bullet_sprite = assets.sprite("bullet");
     bullet_sprite.set_animation(1);
+    let ship_sprite = assets.sprite("ship");
+    let enemy_sprites: Vec<_> =
+        enemy_defs.iter().map(|e| assets.sprite(&e.sprite)).collect();
 
-    //ship sprite config
-    let mut ship_sprite = AnimatedSprite::new(
-        16,
-        24,
-        &[
-            Animation {
-                name: "idle".to_string(),
-                row: 0,
-                frames: 2,
-                fps: 12,
-            },
-            Animation {
-                name: "slight_left".to_string(),
-                row: 1,
-                frames: 2,
-                fps: 12,
-            },
-            Animation {
-                name: "left".to_string(),
-                row: 2,
-                frames: 2,
-                fps: 12,
-            },
-            Animation {
-                name: "slight_right".to_string(),
-                row: 3,
-                frames: 2,
-                fps: 12,
-            },
-            Animation {
-                name: "right".to_string(),
-                row: 4,
-                frames: 2,
-                fps: 12,
-            },
-        ],
-        true,
-        );
-
-
-    //enemy sprite config
-    let mut enemy_small_sprite = AnimatedSprite::new(
-        17,
-        16,
-        &[Animation {
-            name: "enemy_small".to_string(),
-            row: 0,
-            frames: 2,
-            fps: 12,
-        }],
-        true,
-    );
-    let mut enemy_medium_sprite = AnimatedSprite::new(
-        32,
-        16,
-        &[Animation {
-            name: "enemy_medium".to_string(),
-            row: 0,
-            frames: 2,
-            fps: 12,
-        }],
-        true,
-    );
-    let mut enemy_big_sprite = AnimatedSprite::new(
-        32,
-        32,
-        &[Animation {
-            name: "enemy_big".to_string(),
-            row: 0,
-            frames: 2,
-            fps: 12,
-        }],
-        true,
-    );
-
-    //window conifguration
-    let window_style = root_ui()
-        .style_builder()
-        .background(window_background)
-        .background_margin(RectOffset::new(32.0, 76.0, 44.0, 20.0))
-        .margin(RectOffset::new(0.0, -40.0, 0.0, 0.0))
-        .build();
-
-    let button_style = root_ui()
-        .style_builder()
-        .background(button_background)
-        .background_clicked(button_clicked_background)
-        .background_margin(RectOffset::new(16.0, 16.0, 16.0, 16.0))
-        .margin(RectOffset::new(16.0, 0.0, -8.0, -8.0))
-        .font(&font)
-        .unwrap()
-        .text_color(WHITE)
-        .font_size(64)
-        .build();
-
-    let label_style = root_ui()
-        .style_builder()
-        .font(&font)
-        .unwrap()
-        .text_color(WHITE)
-        .font_size(28)
-        .build();
+    //create an exhaust stream for the player
+    let exhaust = Exhaust::new(&effects);
 
-    let ui_skin = Skin {
-        window_style,
-        button_style,
-        label_style,
-        ..root_ui().default_skin()
-    };
+    let font = locale.language_fits_ui_font();
+    let ui_skin = skin::build(&assets, font);
     root_ui().push_skin(&ui_skin);
-    let window_size = vec2(370.0, 320.0);
+    let focus_skin = skin::build_focus(&assets, font, &ui_skin);
+
+    let viewport = Viewport::new();
+    let postfx = PostFx::new(&settings, viewport.pixel_size());
+    let impacts = Impacts::new(&settings);
+    let wipe = Wipe::new();
+    let outline = Outline::new();
+    let hud = Hud::new(&assets);
+    let stages = stages::load(assets.data("stages"));
+
+    //dev builds watch the assets and shaders and reload them on change
+    let mut hot_reload = dev_mode().then(|| {
+        let shaders = vec![
+            starfield.shader_files().to_vec(),
+            postfx.shader_files(),
+            wipe.shader_files().to_vec(),
+            outline.shader_files().to_vec(),
+        ];
+        HotReload::new(&assets, shaders)
+    });
 
-    //play music
-    play_sound(
-        &theme_music,
-        PlaySoundParams {
-            looped: true,
-            volume: 0.2,
-        },
-    );
+    let mut game = Game {
+        settings,
+        assets,
+        audio,
+        music,
+        starfield,
+        background: Background::new(),
+        stages,
+        postfx,
+        impacts,
+        wipe,
+        outline,
+        hud,
+        focus_skin,
+        gamepad: Gamepad::new(),
+        window_focus: WindowFocus::new(),
+        locale,
+        effects,
+        effect_pool,
+        exhaust,
+        enemy_defs,
+        enemy_textures,
+        enemy_sprites,
+        weapon_defs,
+        ship_texture,
+        ship_sprite,
+        bullet_texture,
+        bullet_sprite,
+        score: 0,
+        high_score: 0,
+        high_scores: HighScores::load(),
+        lifetime,
+    };
+    let mut states = StateStack::new(Box::new(MainMenu::new()), &mut game);
+
+    //a shader that fails at startup is only logged by its owner, compiling
+    //them again puts the failure on the overlay like a failed reload
+    if let Some(hot_reload) = &mut hot_reload {
+        let results = [
+            (game.starfield.shader_files()[0].path, game.starfield.reload()),
+            (game.postfx.shader_files()[0].path, game.postfx.reload()),
+            (game.wipe.shader_files()[0].path, game.wipe.reload()),
+            (game.outline.shader_files()[0].path, game.outline.reload()),
+        ];
+        for (path, result) in results {
+            if result.is_err() {
+                hot_reload.report(&Watched::Shader(path), result);
+            }
+        }
+    }
 
     loop {
-        clear_background(BLACK);
-        
-        material.set_uniform("iResolution", (screen_width(), screen_height()));
-        material.set_uniform("direction_modifier", direction_modifier);
-        gl_use_material(&material);
-        draw_texture_ex(
-            &render_target.texture,
-            0.,
-            0.,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(screen_width(), screen_height())),
-                ..Default::default()
-            },
-        );
-        gl_use_default_material();
+        game.gamepad.update();
+        game.window_focus.update();
+        //runs before anything else moves, so the long frame that gave the
+        //window away can still pause the game before it's simulated
+        states.update(&mut game);
+        let frozen = states.frozen();
+
+        let stage = stages::stage_for(&game.stages, game.score);
+        if game.background.set_stage(stage, &game.stages[stage], &game.assets) {
+            game.starfield.set_preset(&game.stages[stage].starfield);
+            game.starfield.warp(game.stages[stage].warp);
+        }
+        if !frozen {
+            game.background.update(get_frame_time());
+            game.starfield.update(get_frame_time());
+        }
+        //only steered while playing, set again by the state every frame
+        game.starfield.steer(0.0);
 
+        //a frozen state leaves the playfield target alone, so it keeps
+        //showing the last frame drawn into it
+        clear_background(BLACK);
+        if !frozen {
+            game.starfield.render();
+            let (shake, tilt) = game.impacts.shake();
+            viewport.begin(shake, tilt);
+            game.starfield.draw(Rect::new(0.0, 0.0, viewport::WIDTH, viewport::HEIGHT));
+            game.background.draw(game.starfield.direction_modifier());
+            states.draw(&mut game);
+        }
+        game.postfx.update(get_frame_time());
+        game.impacts.update(get_frame_time());
+        viewport.end(&game.postfx);
+        game.impacts.draw_flash();
+
+        //text is drawn at window resolution over the scaled up playfield
+        states.draw_ui(&mut game);
+
+        //dev builds show the playfield position under the cursor
+        if dev_mode() {
+            let world = Viewport::mouse_position();
+            let (x, y) = mouse_position();
+            draw_text(format!("{:.0}, {:.0}", world.x, world.y), x + 12.0, y, 16.0, GRAY);
+        }
 
-        match game_state {
-            GameState::MainMenu => {
-                root_ui().window(
-                    hash!(),
-                    vec2(
-                        screen_width() / 2.0 - window_size.x / 2.0,
-                        screen_height() / 2.0 - window_size.y / 2.0,
-                    ),
-                    window_size,
-                    |ui| {
-                        ui.label(vec2(80.0,-34.0), "SHAPEWAR");
-                        if ui.button(vec2(65.0, 25.0), "Play") {
-                            squares.clear();
-                            bullets.clear();
-                            explosions.clear();
-                            circle.x = screen_width() / 2.0;
-                            circle.y = screen_height() / 2.0;
-                            score = 0;
-                            game_state = GameState::Playing;
+        game.audio.update();
+        game.music.update(game.audio.music_gain());
+
+        if let Some(hot_reload) = &mut hot_reload {
+            for change in hot_reload.poll() {
+                let result = match &change {
+                    Watched::Shader(path) if game.postfx.owns(path) => game.postfx.reload(),
+                    Watched::Shader(path) if game.wipe.owns(path) => game.wipe.reload(),
+                    Watched::Shader(path) if game.outline.owns(path) => game.outline.reload(),
+                    Watched::Shader(_) => game.starfield.reload(),
+                    Watched::Asset(kind, name) => game.assets.reload(*kind, name),
+                };
+                if result.is_ok()
+                    && let Watched::Asset(kind, name) = &change
+                {
+                    match kind {
+                        AssetKind::Image => game.rebuild_skin(),
+                        //the glyphs the font covers decide where the fallback is used
+                        AssetKind::Font => {
+                            game.locale = Locale::load(&game.assets, &game.settings.language);
+                            game.hud = Hud::new(&game.assets);
+                            game.rebuild_skin();
                         }
-                        if ui.button(vec2(65.0, 125.0), "Quit") {
-                            std::process::exit(0);
+                        AssetKind::Data if name.starts_with("lang_") => {
+                            game.locale = Locale::load(&game.assets, &game.settings.language);
+                            game.rebuild_skin();
                         }
-                    },
-                );
-
-
-//                let title = "SHAPEWAR";
-//                let title_dimensions = measure_text(title, None, 150, 1.0);
-//                draw_text(
-//                    title,
-//                    screen_width() / 2.0 - title_dimensions.width / 2.0,
-//                    title_dimensions.height + 10.0,
-//                    150.0,
-//                    WHITE,
-//                );
-
-
-            },
-            GameState::Playing => {         
-                set_sound_volume(&theme_music, 0.8);
-                
-                //get player input
-                ship_sprite.set_animation(0);
-                let delta_time = get_frame_time();
-                let frame_time = get_time();
-                if is_key_down(KeyCode::Right) {
-                    circle.x+= circle.speed * delta_time;
-                    direction_modifier += 0.05 * delta_time;
-                    ship_sprite.set_animation(4);
-                }
-                if is_key_down(KeyCode::Left) {
-                    circle.x-= circle.speed * delta_time;
-                    direction_modifier -= 0.05 * delta_time;
-                    ship_sprite.set_animation(2);
-                }
-                if is_key_down(KeyCode::Down) {
-                    circle.y+= circle.speed * delta_time;
-                }
-                 if is_key_down(KeyCode::Up) {
-                    circle.y-= circle.speed * delta_time;
-                }
-                
-                if is_key_pressed(KeyCode::Escape) {
-                    game_state = GameState::Paused;
-                }
-
-                // prevent player from moving off screen
-                circle.x = clamp(circle.x, circle.size / 2.0, screen_width()-(circle.size / 2.0));
-                circle.y = clamp(circle.y, circle.size / 2.0, screen_height()-(circle.size / 2.0));
-                
-                //shot
-                if is_key_pressed(KeyCode::Space) && frame_time - last_shot > 0.5{ 
-                    bullets.push(Shape {
-                       x: circle.x,
-                       y: circle.y -24.0,
-                       speed: circle.speed * 2.0,
-                       size: 32.0,
-                       collided: false,
-                    });
-                    play_sound_once(&sound_laser);
-                    last_shot = frame_time;
-                }
-
-
-                //create randomly sized squares
-                if rand::gen_range(0, 99) >= 95 {        
-                    let size = rand::gen_range(16.0, 64.0);
-                    squares.push(Shape {
-                        size,
-                        speed: rand::gen_range(50.0, 150.0),
-                        x: rand::gen_range(size / 2.0, screen_width() - size / 2.0),
-                        y: -size,
-                        collided: false,
-                    });
-                }
-
-                //move non player objects
-                for square in &mut squares {
-                    square.y += square.speed * delta_time;
-                }
-                for bullet in &mut bullets {
-                    bullet.y -= bullet.speed * delta_time;
-                }
-
-                ship_sprite.update();
-                bullet_sprite.update();
-                enemy_small_sprite.update();
-                enemy_medium_sprite.update();
-                enemy_big_sprite.update();
-
-
-                //remove nonplayer objects if they have left the screen
-                squares.retain(|square| square.y < screen_height() + square.size);
-                bullets.retain(|bullet| bullet.y < screen_height() + bullet.size);
-
-                //remove 'dead' objects
-                squares.retain(|square| !square.collided);
-                bullets.retain(|bullet| !bullet.collided);
-                explosions.retain(|(explosion, _)| explosion.config.emitting);
-
-                if squares.iter().any(|square| circle.collides_with(square)) {
-                    if score == high_score {
-                        fs::write("highscore.dat", high_score.to_string()).ok();
-                        gitgud = true;
-                    }
-                    game_state = GameState::GameOver;
-                }
-
-                for square in squares.iter_mut() {
-                    for bullet in bullets.iter_mut() {
-                        if bullet.collides_with(square) {
-                            bullet.collided = true;
-                            square.collided = true;
-                            score += square.size.round() as u32 / 2;
-                            score += square.size.round() as u32 / 2; 
-                            high_score = high_score.max(score);
-                            explosions.push((
-                                    Emitter::new(EmitterConfig {
-                                        amount: square.size.round() as u32 * 2,
-                                        initial_velocity: square.size * 2.0,
-                                        texture: Some(explosion_texture.clone()),
-                                        ..particle_explosion()
-                                    }),
-                                    vec2(square.x, square.y),
-                                ));
-                            play_sound_once(&sound_explosion);
+                        AssetKind::Sound => {
+                            if game.audio.has_effect(name) {
+                                game.audio.load_effect(name, game.assets.sound(name), Bus::Sfx).await;
+                            }
+                            if game.music.has_track(name) {
+                                game.music.reload_track(name, &game.assets).await;
+                            }
                         }
+                        AssetKind::Data if name == "stages" => {
+                            game.stages = stages::load(game.assets.data("stages"));
+                            game.background = Background::new();
+                        }
+                        AssetKind::Data if name == "effects" => game.load_effects(),
+                        AssetKind::Data if name == "enemies" => game.load_enemies(),
+                        AssetKind::Data if name == "weapons" => {
+                            game.weapon_defs = weapons::load(game.assets.data("weapons"));
+                        }
+                        _ => {}
                     }
                 }
-
-                //draw everything
-                let circle_pos = vec2(circle.x, circle.y - (circle.size / 2.0));
-                exhaust.draw(circle_pos);
-                
-                let ship_frame = ship_sprite.frame();
-                draw_texture_ex(
-                    &ship_texture,
-                    circle.x - ship_frame.dest_size.x,
-                    circle.y - ship_frame.dest_size.y,
-                    WHITE,
-                    DrawTextureParams {
-                        dest_size: Some(ship_frame.dest_size * 2.0),
-                        source: Some(ship_frame.source_rect),
-                        ..Default::default()
-                    },
-                );
-                
-                let enemy_small = enemy_small_sprite.frame();
-                let enemy_medium = enemy_medium_sprite.frame();
-                let enemy_big = enemy_big_sprite.frame();
-                for square in &squares {
-                    if square.size <= 32.0 {
-                    draw_texture_ex(
-                        &enemy_small_texture,
-                        square.x - square.size / 2.0,
-                        square.y - square.size / 2.0,
-                        WHITE,
-                        DrawTextureParams {
-                            dest_size: Some(vec2(square.size, square.size)),
-                            source: Some(enemy_small.source_rect),
-                            ..Default::default()
-                        },
-                    );
-                    } else if square.size <=48.0 {
-                    draw_texture_ex(
-                        &enemy_medium_texture,
-                        square.x - square.size / 2.0,
-                        square.y - square.size / 2.0,
-                        WHITE,
-                        DrawTextureParams {
-                            dest_size: Some(vec2(square.size, square.size )),
-                            source: Some(enemy_medium.source_rect),
-                            ..Default::default()
-                        },
-                    );
-                    } else {
-                    draw_texture_ex(
-                        &enemy_big_texture,
-                        square.x - square.size / 2.0,
-                        square.y - square.size / 2.0,
-                        WHITE,
-                        DrawTextureParams {
-                            dest_size: Some(vec2(square.size, square.size)),
-                            source: Some(enemy_big.source_rect),
-                            ..Default::default()
-                        },
-                    );
-                    }
-                }
-
-                for (explosion, coords) in explosions.iter_mut() {
-                    explosion.draw(*coords);
-                }
-
-                let bullet_frame = bullet_sprite.frame();
-                for bullet in  &bullets {
-                    draw_texture_ex(
-                        &bullet_texture,
-                        bullet.x - bullet.size / 2.0,
-                        bullet.y -bullet.size / 2.0,
-                        WHITE,
-                        DrawTextureParams {
-                            dest_size: Some(vec2(bullet.size, bullet.size)),
-                            source: Some(bullet_frame.source_rect),
-                            ..Default::default()
-                        },
-                    );
-                }
-
-                draw_text(
-                    format!("Score: {}", score).as_str(),
-                    10.0,
-                    35.0,
-                    25.0,
-                    WHITE,
-                );
-                let highscore_text = format!("High score: {}", high_score);
-                let text_dimensions = measure_text(highscore_text.as_str(), None, 25, 1.0);
-                draw_text(
-                    highscore_text.as_str(),
-                    screen_width() - text_dimensions.width -10.0,
-                    35.0,
-                    25.0,
-                    WHITE,
-                );
-            },           
-            GameState::Paused => {
-                set_sound_volume(&theme_music, 0.0);
-                if is_key_pressed(KeyCode::Escape) {
-                    game_state = GameState::Playing;
-                }
-                let text = "Paused";
-                let text_dimensions = measure_text(text, None, 50, 1.0);
-                
-
-                draw_circle(circle.x, circle.y, circle.size / 2.0, YELLOW);
-                
-                for square in &squares {
-                    draw_rectangle(
-                        square.x - square.size / 2.0,
-                        square.y - square.size / 2.0,
-                        square.size,
-                        square.size,
-                        GREEN,
-                    );
-                }
-
-                for bullet in  &bullets {
-                    draw_circle(bullet.x, bullet.y, bullet.size / 2.0, RED);
-                }
-
-                draw_text(
-                    text,
-                    screen_width() / 2.0 - text_dimensions.width / 2.0,
-                    screen_height() / 2.0,
-                    50.0,
-                    WHITE,
-                );
-            },       
-            GameState::GameOver => {
-                if is_key_pressed(KeyCode::Space) {
-                    game_state = GameState::MainMenu;
-                }
-                let text = "GAME OVER!";
-                let text_dimensions = measure_text(text, None, 50, 1.0);
-                draw_text(
-                    text,
-                    screen_width() / 2.0 - text_dimensions.width / 2.0,
-                    screen_height() / 2.0,
-                    50.0,
-                    RED,
-                );
-                if gitgud {
-                    let text = "New High Score!";
-                    let text_dimensions = measure_text(text, None, 30, 1.0);
-                    draw_text(
-                        text,
-                        screen_width() / 2.0 - text_dimensions.width / 2.0,
-                        screen_height() / 2.0 + text_dimensions.height,
-                        30.0,
-                        RED,
-                    );
-                }
-            },
+                hot_reload.report(&change, result);
+            }
+            hot_reload.draw_overlay();
         }
-
         next_frame().await
     }
 }
@@ -0,0 +1,218 @@
+use macroquad::audio::{load_sound_from_bytes, play_sound, set_sound_volume, stop_sound, PlaySoundParams, Sound};
+use macroquad::prelude::*;
+
+use crate::assets::Assets;
+use crate::config::Config;
+use crate::loading::LoadingScreen;
+
+//one looping sound, shared by every cue that references the same track so
+//switching between cues on the same track never restarts it
+struct Channel {
+    track: String,
+    sound: Sound,
+    level: f32,
+    target: f32,
+    playing: bool,
+}
+
+struct PlaylistEntry {
+    track: String,
+    //seconds before moving on to the next entry, loops forever when unset
+    length: Option<f32>,
+}
+
+//a `[section]` of the playlist config, selected by game state
+struct Cue {
+    name: String,
+    playlist: Vec<PlaylistEntry>,
+    volume: f32,
+    intense_volume: f32,
+    stem: Option<String>,
+    fade: f32,
+}
+
+pub struct MusicController {
+    channels: Vec<Channel>,
+    cues: Vec<Cue>,
+    current: usize,
+    track: usize,
+    track_time: f32,
+    intensity: f32,
+    intensity_target: f32,
+}
+
+impl MusicController {
+    pub async fn load(playlist: &str, assets: &Assets, loading: &mut LoadingScreen<'_>) -> Self {
+        let config = Config::parse(playlist);
+
+        let mut cues = vec![];
+        for section in config.sections.iter().skip(1) {
+            let volume = section.get_f32("volume", 1.0);
+            cues.push(Cue {
+                name: section.name.clone(),
+                playlist: section
+                    .get_all("track")
+                    .map(|entry| match entry.split_once(',') {
+                        Some((track, length)) => PlaylistEntry {
+                            track: track.trim().to_string(),
+                            length: length.trim().parse().ok(),
+                        },
+                        None => PlaylistEntry {
+                            track: entry.to_string(),
+                            length: None,
+                        },
+                    })
+                    .collect(),
+                volume,
+                intense_volume: section.get_f32("intense_volume", volume),
+                stem: section.get("stem").map(str::to_string),
+                fade: section.get_f32("fade", 1.0),
+            });
+        }
+
+        let mut tracks: Vec<&String> = vec![];
+        for cue in &cues {
+            for track in cue.playlist.iter().map(|e| &e.track).chain(cue.stem.as_ref()) {
+                if !tracks.contains(&track) {
+                    tracks.push(track);
+                }
+            }
+        }
+
+        //decoding a whole track is the slowest part of startup
+        loading.expect(tracks.len());
+        let mut channels = vec![];
+        for track in tracks {
+            loading.frame().await;
+            //a track that fails to load is left out, its cues play silence
+            match load_sound_from_bytes(assets.sound(track)).await {
+                Ok(sound) => channels.push(Channel {
+                    track: track.clone(),
+                    sound,
+                    level: 0.0,
+                    target: 0.0,
+                    playing: false,
+                }),
+                Err(e) => error!("music track `{}`: {}", track, e),
+            }
+            loading.finish(1);
+        }
+
+        MusicController {
+            channels,
+            cues,
+            current: 0,
+            track: 0,
+            track_time: 0.0,
+            intensity: 0.0,
+            intensity_target: 0.0,
+        }
+    }
+
+    pub fn has_track(&self, track: &str) -> bool {
+        self.channels.iter().any(|c| c.track == track)
+    }
+
+    pub async fn reload_track(&mut self, track: &str, assets: &Assets) {
+        for channel in self.channels.iter_mut().filter(|c| c.track == track) {
+            let sound = match load_sound_from_bytes(assets.sound(track)).await {
+                Ok(sound) => sound,
+                Err(e) => {
+                    error!("music track `{}`: {}", track, e);
+                    continue;
+                }
+            };
+            if channel.playing {
+                stop_sound(&channel.sound);
+                channel.playing = false;
+            }
+            channel.sound = sound;
+        }
+    }
+
+    //switch to the named cue, crossfading unless it shares the current track
+    pub fn cue(&mut self, name: &str) {
+        let Some(index) = self.cues.iter().position(|c| c.name == name) else {
+            return;
+        };
+        if index == self.current {
+            return;
+        }
+
+        let playing = self.current_track().map(str::to_string);
+        self.current = index;
+        self.track = 0;
+        self.track_time = 0.0;
+        //keep the playlist position when the new cue shares the track
+        if let Some(playing) = playing
+            && let Some(track) = self.cues[index].playlist.iter().position(|e| e.track == playing)
+        {
+            self.track = track;
+        }
+    }
+
+    //0.0 is calm, 1.0 is the busiest the current cue gets
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity_target = intensity.clamp(0.0, 1.0);
+    }
+
+    fn current_track(&self) -> Option<&str> {
+        let cue = self.cues.get(self.current)?;
+        cue.playlist.get(self.track).map(|e| e.track.as_str())
+    }
+
+    pub fn update(&mut self, gain: f32) {
+        let delta_time = get_frame_time();
+        if self.cues.is_empty() {
+            return;
+        }
+
+        //intensity eases over a couple of seconds so the mix doesn't pump
+        let step = delta_time * 0.5;
+        self.intensity += (self.intensity_target - self.intensity).clamp(-step, step);
+
+        let cue = &self.cues[self.current];
+        self.track_time += delta_time;
+        if let Some(length) = cue.playlist.get(self.track).and_then(|e| e.length)
+            && self.track_time >= length
+        {
+            self.track = (self.track + 1) % cue.playlist.len();
+            self.track_time = 0.0;
+        }
+
+        let volume = cue.volume + (cue.intense_volume - cue.volume) * self.intensity;
+        let track = cue.playlist.get(self.track).map(|e| e.track.as_str());
+        let step = delta_time / cue.fade.max(0.01);
+        for channel in &mut self.channels {
+            let stem = cue.stem.as_ref() == Some(&channel.track);
+            let used = Some(channel.track.as_str()) == track || stem;
+            channel.target = if !used {
+                0.0
+            } else if stem {
+                volume * self.intensity
+            } else {
+                volume
+            };
+            channel.level += (channel.target - channel.level).clamp(-step, step);
+
+            //a track the cue still uses keeps running at volume 0, so a cue
+            //that silences it (like pausing) doesn't rewind it
+            if used && !channel.playing {
+                play_sound(
+                    &channel.sound,
+                    PlaySoundParams {
+                        looped: true,
+                        volume: 0.0,
+                    },
+                );
+                channel.playing = true;
+            } else if !used && channel.level == 0.0 && channel.playing {
+                stop_sound(&channel.sound);
+                channel.playing = false;
+            }
+            if channel.playing {
+                set_sound_volume(&channel.sound, channel.level * gain);
+            }
+        }
+    }
+}
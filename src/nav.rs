@@ -0,0 +1,123 @@
+use gilrs::Button;
+use macroquad::prelude::*;
+use macroquad::ui::{Id, Skin, Ui};
+use std::ops::Range;
+
+use crate::gamepad::Gamepad;
+
+//steps a slider takes from one end to the other
+const SLIDER_STEPS: f32 = 20.0;
+
+//keyboard and pad focus for the widgets of a root_ui window, which on their
+//own only answer the mouse. up and down (or the d-pad) move between widgets,
+//left and right change sliders and choices, and enter or the south face
+//button (A on most pads) presses buttons and ticks checkboxes
+pub struct Nav {
+    focused: usize,
+    //widgets seen last frame, to wrap around the ends
+    count: usize,
+    next: usize,
+    activate: bool,
+    //-1 for left, 1 for right, this frame
+    sideways: i32,
+    //left or right on a button, moves the focus at the next `begin`
+    shift: i32,
+    //set while a wipe runs, nothing can be changed
+    blocked: bool,
+}
+
+impl Nav {
+    pub fn new() -> Self {
+        Nav {
+            focused: 0,
+            count: 0,
+            next: 0,
+            activate: false,
+            sideways: 0,
+            shift: 0,
+            blocked: false,
+        }
+    }
+
+    //reads the keys and pad, called every frame before the window is built
+    pub fn begin(&mut self, pad: &Gamepad, blocked: bool) {
+        if self.next > 0 {
+            self.count = self.next;
+        }
+        self.next = 0;
+        self.blocked = blocked;
+        self.sideways = 0;
+        if self.count > 0 && !blocked {
+            let down = is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::Tab) || pad.pressed(Button::DPadDown);
+            let up = is_key_pressed(KeyCode::Up) || pad.pressed(Button::DPadUp);
+            let step = down as i32 - up as i32 + self.shift;
+            self.focused = (self.focused as i32 + step).rem_euclid(self.count as i32) as usize;
+            let right = is_key_pressed(KeyCode::Right) || pad.pressed(Button::DPadRight);
+            let left = is_key_pressed(KeyCode::Left) || pad.pressed(Button::DPadLeft);
+            self.sideways = right as i32 - left as i32;
+        }
+        self.shift = 0;
+        //not space, a player still firing as the run ends would hit retry
+        self.activate = !blocked
+            && (is_key_pressed(KeyCode::Enter)
+                || is_key_pressed(KeyCode::KpEnter)
+                || pad.pressed(Button::South));
+    }
+
+    //whether the next widget has the focus, drawn with `focus_skin` if so
+    fn widget<T>(&mut self, ui: &mut Ui, focus_skin: &Skin, draw: impl FnOnce(&mut Ui, bool) -> T) -> T {
+        let focused = self.next == self.focused;
+        self.next += 1;
+        if focused {
+            ui.push_skin(focus_skin);
+        }
+        let result = draw(ui, focused);
+        if focused {
+            ui.pop_skin();
+        }
+        result
+    }
+
+    //`ui.button` that is also pressed by the keyboard while focused
+    pub fn button(&mut self, ui: &mut Ui, focus_skin: &Skin, position: impl Into<Option<Vec2>>, label: &str) -> bool {
+        let (activate, sideways) = (self.activate, self.sideways);
+        let (clicked, focused) = self.widget(ui, focus_skin, |ui, focused| (ui.button(position, label), focused));
+        //buttons laid out side by side are stepped through with left and right
+        if focused {
+            self.shift = sideways;
+        }
+        !self.blocked && (clicked || (focused && activate))
+    }
+
+    pub fn slider(&mut self, ui: &mut Ui, focus_skin: &Skin, id: Id, label: &str, range: Range<f32>, value: &mut f32) {
+        let sideways = self.sideways as f32;
+        self.widget(ui, focus_skin, |ui, focused| {
+            if focused && sideways != 0.0 {
+                let step = (range.end - range.start) / SLIDER_STEPS;
+                *value = (*value + step * sideways).clamp(range.start, range.end);
+            }
+            ui.slider(id, label, range, value);
+        });
+    }
+
+    pub fn checkbox(&mut self, ui: &mut Ui, focus_skin: &Skin, id: Id, label: &str, value: &mut bool) {
+        let toggle = self.activate || self.sideways != 0;
+        self.widget(ui, focus_skin, |ui, focused| {
+            if focused && toggle {
+                *value = !*value;
+            }
+            ui.checkbox(id, label, value);
+        });
+    }
+
+    //left and right step through the choices, wrapping at the ends
+    pub fn combo_box(&mut self, ui: &mut Ui, focus_skin: &Skin, id: Id, label: &str, choices: &[&str], index: &mut usize) {
+        let sideways = self.sideways;
+        self.widget(ui, focus_skin, |ui, focused| {
+            if focused && sideways != 0 && !choices.is_empty() {
+                *index = (*index as i32 + sideways).rem_euclid(choices.len() as i32) as usize;
+            }
+            ui.combo_box(id, label, choices, &mut *index);
+        });
+    }
+}
@@ -0,0 +1,12 @@
+#version 100
+precision lowp float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec4 outline_color;
+
+// the sprite's silhouette in a flat colour
+void main() {
+    gl_FragColor = vec4(outline_color.rgb, outline_color.a * texture2D(Texture, uv).a);
+}
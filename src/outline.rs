@@ -0,0 +1,73 @@
+use macroquad::miniquad::{BlendFactor, BlendState, BlendValue, Equation, PipelineParams};
+use macroquad::prelude::*;
+
+use crate::shader::{self, ShaderFile, OUTLINE_FRAGMENT, POSTFX_VERTEX};
+
+//playfield units, one pixel of the low resolution playfield
+const WIDTH: f32 = 2.0;
+
+//solid rims around sprites for the high contrast mode, drawn as the
+//sprite's silhouette nudged out in every direction underneath it
+pub struct Outline {
+    //left empty when the shader fails to compile, sprites are drawn without
+    material: Option<Material>,
+}
+
+fn compile() -> Result<Material, String> {
+    shader::compile(
+        &POSTFX_VERTEX,
+        &OUTLINE_FRAGMENT,
+        MaterialParams {
+            uniforms: vec![UniformDesc::new("outline_color", UniformType::Float4)],
+            pipeline_params: PipelineParams {
+                color_blend: Some(BlendState::new(
+                    Equation::Add,
+                    BlendFactor::Value(BlendValue::SourceAlpha),
+                    BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+}
+
+impl Outline {
+    pub fn new() -> Self {
+        Outline {
+            material: compile()
+                .inspect_err(|e| error!("{}: {}", OUTLINE_FRAGMENT.path, e))
+                .ok(),
+        }
+    }
+
+    //the vertex shader is shared with, and watched for, the post processing
+    pub fn shader_files(&self) -> [&'static ShaderFile; 1] {
+        [&OUTLINE_FRAGMENT]
+    }
+
+    pub fn owns(&self, path: &str) -> bool {
+        path == OUTLINE_FRAGMENT.path
+    }
+
+    //keeps the previous material if the new source doesn't compile
+    pub fn reload(&mut self) -> Result<(), String> {
+        self.material = Some(compile()?);
+        Ok(())
+    }
+
+    //draw_texture_ex, with a rim of `rim` around the sprite when given one
+    pub fn draw(&self, texture: &Texture2D, x: f32, y: f32, color: Color, params: DrawTextureParams, rim: Option<Color>) {
+        if let Some(rim) = rim
+            && let Some(material) = &self.material
+        {
+            material.set_uniform("outline_color", rim.to_vec());
+            gl_use_material(material);
+            for (dx, dy) in [(-1.0, 0.0), (1.0, 0.0), (0.0, -1.0), (0.0, 1.0)] {
+                draw_texture_ex(texture, x + dx * WIDTH, y + dy * WIDTH, WHITE, params.clone());
+            }
+            gl_use_default_material();
+        }
+        draw_texture_ex(texture, x, y, color, params);
+    }
+}
@@ -0,0 +1,62 @@
+use macroquad::prelude::*;
+
+//colours that tell the player's side from the enemies', with sets picked
+//from the okabe-ito palette for the common kinds of colour blindness
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Palette {
+    Default,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 4] = [
+        Palette::Default,
+        Palette::Deuteranopia,
+        Palette::Protanopia,
+        Palette::Tritanopia,
+    ];
+
+    //settings value, and string key in the languages
+    pub fn key(self) -> &'static str {
+        match self {
+            Palette::Default => "default",
+            Palette::Deuteranopia => "deuteranopia",
+            Palette::Protanopia => "protanopia",
+            Palette::Tritanopia => "tritanopia",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Palette> {
+        Palette::ALL.into_iter().find(|p| p.key() == value)
+    }
+
+    //multiplied over the player's bullet sprites, the default leaves them be
+    pub fn player_projectile(self) -> Color {
+        match self {
+            Palette::Default => WHITE,
+            _ => self.player(),
+        }
+    }
+
+    //outlines in high contrast mode
+    pub fn player(self) -> Color {
+        match self {
+            Palette::Default => WHITE,
+            Palette::Deuteranopia | Palette::Protanopia => Color::from_hex(0x56b4e9),
+            Palette::Tritanopia => Color::from_hex(0xd55e00),
+        }
+    }
+
+    //enemy outlines, shown for every palette but the default one even
+    //without high contrast
+    pub fn enemy(self) -> Color {
+        match self {
+            Palette::Default => Color::from_hex(0xff5050),
+            Palette::Deuteranopia => Color::from_hex(0xe69f00),
+            Palette::Protanopia => Color::from_hex(0xf0e442),
+            Palette::Tritanopia => Color::from_hex(0x009e73),
+        }
+    }
+}
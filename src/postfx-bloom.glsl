@@ -0,0 +1,30 @@
+#version 100
+precision highp float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 source_size;
+uniform float strength;
+
+// only what is brighter than this glows
+#define THRESHOLD 0.55
+
+void main() {
+    vec3 base = texture2D(Texture, uv).rgb;
+    vec2 texel = 1. / source_size;
+
+    vec3 glow = vec3(0.);
+    float total = 0.;
+    for (int y = -3; y <= 3; y++) {
+        for (int x = -3; x <= 3; x++) {
+            vec2 offs = vec2(x, y);
+            vec3 s = texture2D(Texture, uv + offs * texel * 1.5).rgb;
+            float w = exp(-dot(offs, offs) / 8.);
+            glow += max(s - THRESHOLD, 0.) * w;
+            total += w;
+        }
+    }
+
+    gl_FragColor = vec4(base + glow / total * strength * 4., 1.);
+}
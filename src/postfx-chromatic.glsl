@@ -0,0 +1,17 @@
+#version 100
+precision highp float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 source_size;
+uniform float strength;
+
+void main() {
+    // channels split outwards from the center, up to a few pixels at the edges
+    vec2 offs = (uv - .5) * strength * 12. / source_size.x;
+    float r = texture2D(Texture, uv + offs).r;
+    float g = texture2D(Texture, uv).g;
+    float b = texture2D(Texture, uv - offs).b;
+    gl_FragColor = vec4(r, g, b, 1.);
+}
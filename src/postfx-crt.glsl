@@ -0,0 +1,32 @@
+#version 100
+precision highp float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 source_size;
+uniform float strength;
+
+void main() {
+    // barrel distortion, anything bent outside the tube is black
+    vec2 cc = uv - .5;
+    float bend = dot(cc, cc) * .2 * strength;
+    vec2 p = uv + cc * (1. + bend) * bend;
+    if (p.x < 0. || p.x > 1. || p.y < 0. || p.y > 1.) {
+        gl_FragColor = vec4(0., 0., 0., 1.);
+        return;
+    }
+
+    vec3 col = texture2D(Texture, p).rgb;
+
+    // one dark line between every row of the playfield's pixels
+    float scan = abs(sin(p.y * source_size.y * 3.14159));
+    col *= mix(1. - .35 * strength, 1. + .1 * strength, scan);
+
+    // faint rgb stripes of a shadow mask
+    float stripe = mod(gl_FragCoord.x, 3.);
+    vec3 mask = vec3(stripe < 1. ? 1. : .85, stripe >= 1. && stripe < 2. ? 1. : .85, stripe >= 2. ? 1. : .85);
+    col *= mix(vec3(1.), mask, strength);
+
+    gl_FragColor = vec4(col, 1.);
+}
@@ -0,0 +1,16 @@
+#version 100
+precision highp float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 source_size;
+uniform float strength;
+
+void main() {
+    vec3 col = texture2D(Texture, uv).rgb;
+    // stretched to the playfield's aspect so the falloff is round
+    vec2 d = (uv - .5) * vec2(source_size.x / source_size.y, 1.);
+    float shade = smoothstep(1.1, .35, length(d));
+    gl_FragColor = vec4(col * mix(1., shade, strength), 1.);
+}
@@ -0,0 +1,204 @@
+use macroquad::prelude::*;
+
+use crate::settings::Settings;
+use crate::shader::{
+    self, ShaderFile, POSTFX_BLOOM, POSTFX_CHROMATIC, POSTFX_CRT, POSTFX_VERTEX, POSTFX_VIGNETTE,
+};
+
+//seconds for a full strength hit's colour fringing to clear
+const ABERRATION_DECAY: f32 = 0.6;
+
+#[derive(Clone, Copy)]
+enum Effect {
+    Bloom,
+    Chromatic,
+    Vignette,
+    Crt,
+}
+
+struct Pass {
+    effect: Effect,
+    fragment: &'static ShaderFile,
+    enabled: bool,
+    //left empty when the shader fails to compile, the pass is skipped
+    material: Option<Material>,
+}
+
+//full screen passes run over the finished playfield, in order. every pass
+//but the last renders into an offscreen target at the playfield's own
+//resolution; the last enabled one draws straight into the window so the crt
+//scanlines and mask land on real screen pixels
+pub struct PostFx {
+    passes: Vec<Pass>,
+    targets: [RenderTarget; 2],
+    aberration: f32,
+    //off with reduced motion, hits don't fringe the picture
+    hits: bool,
+}
+
+fn compile(fragment: &ShaderFile) -> Result<Material, String> {
+    shader::compile(
+        &POSTFX_VERTEX,
+        fragment,
+        MaterialParams {
+            uniforms: vec![
+                UniformDesc::new("source_size", UniformType::Float2),
+                UniformDesc::new("strength", UniformType::Float1),
+            ],
+            ..Default::default()
+        },
+    )
+}
+
+impl PostFx {
+    pub fn new(settings: &Settings, size: Vec2) -> Self {
+        let target = || {
+            let target = render_target(size.x as u32, size.y as u32);
+            target.texture.set_filter(FilterMode::Nearest);
+            target
+        };
+        let passes = [
+            (Effect::Bloom, &POSTFX_BLOOM),
+            (Effect::Chromatic, &POSTFX_CHROMATIC),
+            (Effect::Vignette, &POSTFX_VIGNETTE),
+            (Effect::Crt, &POSTFX_CRT),
+        ]
+        .into_iter()
+        .map(|(effect, fragment)| Pass {
+            effect,
+            fragment,
+            enabled: false,
+            material: compile(fragment)
+                .inspect_err(|e| error!("{}: {}", fragment.path, e))
+                .ok(),
+        })
+        .collect();
+
+        let mut postfx = PostFx {
+            passes,
+            targets: [target(), target()],
+            aberration: 0.0,
+            hits: true,
+        };
+        postfx.apply_settings(settings);
+        postfx
+    }
+
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        for pass in &mut self.passes {
+            pass.enabled = match pass.effect {
+                Effect::Bloom => settings.bloom,
+                Effect::Chromatic => settings.chromatic_aberration,
+                Effect::Vignette => settings.vignette,
+                Effect::Crt => settings.crt,
+            };
+        }
+        self.hits = !settings.reduced_motion;
+    }
+
+    pub fn shader_files(&self) -> Vec<&'static ShaderFile> {
+        std::iter::once(&POSTFX_VERTEX)
+            .chain(self.passes.iter().map(|p| p.fragment))
+            .collect()
+    }
+
+    pub fn owns(&self, path: &str) -> bool {
+        self.shader_files().iter().any(|f| f.path == path)
+    }
+
+    //recompiles every pass, keeping the previous material of any that fail
+    pub fn reload(&mut self) -> Result<(), String> {
+        let mut errors = vec![];
+        for pass in &mut self.passes {
+            match compile(pass.fragment) {
+                Ok(material) => pass.material = Some(material),
+                Err(e) => errors.push(format!("{}: {}", pass.fragment.path, e)),
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
+    //fringes the picture, 1.0 is a heavy hit
+    pub fn hit(&mut self, strength: f32) {
+        if !self.hits {
+            return;
+        }
+        self.aberration = self.aberration.max(strength.clamp(0.0, 1.0));
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.aberration = (self.aberration - delta_time / ABERRATION_DECAY).max(0.0);
+    }
+
+    fn strength(&self, effect: Effect) -> f32 {
+        match effect {
+            Effect::Bloom => 0.6,
+            Effect::Chromatic => self.aberration,
+            Effect::Vignette => 0.7,
+            Effect::Crt => 1.0,
+        }
+    }
+
+    //draws `source` into `dest` on screen through every enabled pass
+    pub fn draw(&self, source: &Texture2D, dest: Rect) {
+        let passes: Vec<(&Pass, &Material)> = self
+            .passes
+            .iter()
+            .filter(|p| p.enabled && self.strength(p.effect) > 0.0)
+            .filter_map(|p| Some((p, p.material.as_ref()?)))
+            .collect();
+
+        let mut input = source.clone();
+        for (i, (pass, material)) in passes.iter().enumerate() {
+            let size = input.size();
+            let last = i + 1 == passes.len();
+            if last {
+                set_default_camera();
+            } else {
+                let target = &self.targets[i % 2];
+                let mut camera = Camera2D::from_display_rect(Rect::new(0.0, 0.0, size.x, size.y));
+                camera.render_target = Some(target.clone());
+                set_camera(&camera);
+            }
+
+            material.set_uniform("source_size", (size.x, size.y));
+            material.set_uniform("strength", self.strength(pass.effect));
+            gl_use_material(material);
+            let rect = if last {
+                dest
+            } else {
+                Rect::new(0.0, 0.0, size.x, size.y)
+            };
+            draw_flipped(&input, rect);
+            gl_use_default_material();
+
+            if !last {
+                input = self.targets[i % 2].texture.clone();
+            }
+        }
+
+        set_default_camera();
+        if passes.is_empty() {
+            draw_flipped(source, dest);
+        }
+    }
+}
+
+//render targets store their rows bottom up, flip to match the screen
+fn draw_flipped(texture: &Texture2D, rect: Rect) {
+    draw_texture_ex(
+        texture,
+        rect.x,
+        rect.y,
+        WHITE,
+        DrawTextureParams {
+            dest_size: Some(rect.size()),
+            flip_y: true,
+            ..Default::default()
+        },
+    );
+}
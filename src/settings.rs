@@ -0,0 +1,171 @@
+use macroquad::prelude::*;
+use std::fs;
+
+use crate::config::Config;
+use crate::difficulty::{Difficulty, Preset};
+use crate::hud::HudLayout;
+use crate::palette::Palette;
+
+const SETTINGS_PATH: &str = "settings.cfg";
+
+pub struct Settings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    //vertical pixels the starfield is rendered at before being upscaled
+    pub background_height: u32,
+    //post processing passes, see postfx.rs
+    pub bloom: bool,
+    pub chromatic_aberration: bool,
+    pub vignette: bool,
+    pub crt: bool,
+    //0.0 turns screen shake or flashes off entirely
+    pub shake_intensity: f32,
+    pub flash_intensity: f32,
+    //pause when the window loses focus or is minimized
+    pub auto_pause: bool,
+    //seconds for a screen transition between states, 0.0 cuts straight over
+    pub transition_time: f32,
+    pub difficulty: Difficulty,
+    //accessibility: colours for the two sides, no shake, flashes or
+    //twinkling stars, outlined sprites and a slower game
+    pub palette: Palette,
+    pub reduced_motion: bool,
+    pub high_contrast: bool,
+    //scales the simulation, sprite animation and particles run at full speed
+    pub game_speed: f32,
+    //code of one of the `lang_*` data files
+    pub language: String,
+    //`hud_*` keys, see hud.rs
+    pub hud: HudLayout,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            master_volume: 1.0,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            background_height: 150,
+            bloom: true,
+            chromatic_aberration: true,
+            vignette: true,
+            crt: false,
+            shake_intensity: 1.0,
+            flash_intensity: 1.0,
+            auto_pause: true,
+            transition_time: 0.6,
+            difficulty: Difficulty {
+                preset: Preset::Normal,
+                dynamic: false,
+            },
+            palette: Palette::Default,
+            reduced_motion: false,
+            high_contrast: false,
+            game_speed: 1.0,
+            language: "en".to_string(),
+            hud: HudLayout::default(),
+        }
+    }
+}
+
+impl Settings {
+    //missing file or keys fall back to the defaults
+    pub fn load() -> Self {
+        let defaults = Settings::default();
+        let Some(config) = Config::load(SETTINGS_PATH) else {
+            return defaults;
+        };
+        let root = config.root();
+        Settings {
+            master_volume: root.get_f32("master_volume", defaults.master_volume),
+            music_volume: root.get_f32("music_volume", defaults.music_volume),
+            sfx_volume: root.get_f32("sfx_volume", defaults.sfx_volume),
+            background_height: root
+                .get_u32("background_height", defaults.background_height)
+                .clamp(32, 2160),
+            bloom: root.get_bool("bloom", defaults.bloom),
+            chromatic_aberration: root
+                .get_bool("chromatic_aberration", defaults.chromatic_aberration),
+            vignette: root.get_bool("vignette", defaults.vignette),
+            crt: root.get_bool("crt", defaults.crt),
+            shake_intensity: root
+                .get_f32("shake_intensity", defaults.shake_intensity)
+                .clamp(0.0, 1.0),
+            flash_intensity: root
+                .get_f32("flash_intensity", defaults.flash_intensity)
+                .clamp(0.0, 1.0),
+            auto_pause: root.get_bool("auto_pause", defaults.auto_pause),
+            transition_time: root
+                .get_f32("transition_time", defaults.transition_time)
+                .clamp(0.0, 3.0),
+            difficulty: Difficulty {
+                preset: root
+                    .get("difficulty")
+                    .and_then(Preset::parse)
+                    .unwrap_or(defaults.difficulty.preset),
+                dynamic: root.get_bool("dynamic_difficulty", defaults.difficulty.dynamic),
+            },
+            palette: root
+                .get("palette")
+                .and_then(Palette::parse)
+                .unwrap_or(defaults.palette),
+            reduced_motion: root.get_bool("reduced_motion", defaults.reduced_motion),
+            high_contrast: root.get_bool("high_contrast", defaults.high_contrast),
+            game_speed: root
+                .get_f32("game_speed", defaults.game_speed)
+                .clamp(0.25, 1.0),
+            language: root.get("language").unwrap_or(&defaults.language).to_string(),
+            hud: HudLayout::load(root),
+        }
+    }
+
+    //written out whole, so the file also documents every option
+    pub fn save(&self) {
+        let text = format!(
+            "master_volume = {}\n\
+             music_volume = {}\n\
+             sfx_volume = {}\n\
+             background_height = {}\n\
+             bloom = {}\n\
+             chromatic_aberration = {}\n\
+             vignette = {}\n\
+             crt = {}\n\
+             shake_intensity = {}\n\
+             flash_intensity = {}\n\
+             auto_pause = {}\n\
+             transition_time = {}\n\
+             difficulty = {}\n\
+             dynamic_difficulty = {}\n\
+             palette = {}\n\
+             reduced_motion = {}\n\
+             high_contrast = {}\n\
+             game_speed = {}\n\
+             language = {}\n\
+             {}",
+            self.master_volume,
+            self.music_volume,
+            self.sfx_volume,
+            self.background_height,
+            self.bloom,
+            self.chromatic_aberration,
+            self.vignette,
+            self.crt,
+            self.shake_intensity,
+            self.flash_intensity,
+            self.auto_pause,
+            self.transition_time,
+            self.difficulty.preset.key(),
+            self.difficulty.dynamic,
+            self.palette.key(),
+            self.reduced_motion,
+            self.high_contrast,
+            self.game_speed,
+            self.language,
+            self.hud.to_config(),
+        );
+        if let Err(e) = fs::write(SETTINGS_PATH, text) {
+            error!("couldn't save {}: {}", SETTINGS_PATH, e);
+        }
+    }
+}
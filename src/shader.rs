@@ -0,0 +1,60 @@
+use macroquad::prelude::*;
+use std::fs;
+
+use crate::assets::dev_mode;
+
+//shaders are compiled into the binary, dev builds read them from disk instead
+//so they can be edited and reloaded while the game runs
+pub struct ShaderFile {
+    pub path: &'static str,
+    embedded: &'static str,
+}
+
+macro_rules! shader_file {
+    ($file:literal) => {
+        ShaderFile {
+            path: concat!("src/", $file),
+            embedded: include_str!($file),
+        }
+    };
+}
+
+pub static STARFIELD_VERTEX: ShaderFile = shader_file!("starfield-vertex.glsl");
+pub static STARFIELD_FRAGMENT: ShaderFile = shader_file!("starfield-shader.glsl");
+pub static POSTFX_VERTEX: ShaderFile = shader_file!("postfx-vertex.glsl");
+pub static POSTFX_BLOOM: ShaderFile = shader_file!("postfx-bloom.glsl");
+pub static POSTFX_CHROMATIC: ShaderFile = shader_file!("postfx-chromatic.glsl");
+pub static POSTFX_VIGNETTE: ShaderFile = shader_file!("postfx-vignette.glsl");
+pub static POSTFX_CRT: ShaderFile = shader_file!("postfx-crt.glsl");
+pub static WIPE_FRAGMENT: ShaderFile = shader_file!("wipe.glsl");
+pub static OUTLINE_FRAGMENT: ShaderFile = shader_file!("outline.glsl");
+
+impl ShaderFile {
+    pub fn source(&self) -> String {
+        if dev_mode()
+            && let Ok(source) = fs::read_to_string(self.path)
+        {
+            return source;
+        }
+        self.embedded.to_string()
+    }
+}
+
+//compile errors come back as the driver's log rather than a panic
+pub fn compile(
+    vertex: &ShaderFile,
+    fragment: &ShaderFile,
+    params: MaterialParams,
+) -> Result<Material, String> {
+    load_material(
+        ShaderSource::Glsl {
+            vertex: &vertex.source(),
+            fragment: &fragment.source(),
+        },
+        params,
+    )
+    .map_err(|e| match e {
+        macroquad::Error::ShaderError(e) => e.to_string(),
+        e => e.to_string(),
+    })
+}
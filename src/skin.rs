@@ -0,0 +1,74 @@
+use macroquad::prelude::*;
+use macroquad::ui::{root_ui, Skin, Style, StyleBuilder};
+
+use crate::assets::Assets;
+
+//labels of the focused slider, checkbox or choice
+const FOCUS_COLOR: Color = Color::new(1.0, 0.8, 0.25, 1.0);
+
+//the font is only missing in dev builds, where the default one stands in.
+//it's also left out for languages it has no glyphs for
+fn with_font(assets: &Assets, font: bool, builder: StyleBuilder) -> StyleBuilder {
+    match assets.font("ui") {
+        Some(bytes) if font => builder.font(bytes).unwrap(),
+        _ => builder,
+    }
+}
+
+fn button_style(assets: &Assets, font: bool, background: &str) -> Style {
+    with_font(assets, font, root_ui().style_builder())
+        .background(assets.image(background))
+        .background_clicked(assets.image("button_clicked_background"))
+        .background_margin(RectOffset::new(16.0, 16.0, 16.0, 16.0))
+        .margin(RectOffset::new(16.0, 0.0, -8.0, -8.0))
+        .text_color(WHITE)
+        .font_size(64)
+        .build()
+}
+
+fn label_style(assets: &Assets, font: bool, color: Color) -> Style {
+    with_font(assets, font, root_ui().style_builder())
+        .text_color(color)
+        .font_size(28)
+        .build()
+}
+
+//rebuilt whenever one of the ui images is hot reloaded or the language changes
+pub fn build(assets: &Assets, font: bool) -> Skin {
+    //window conifguration
+    let window_style = root_ui()
+        .style_builder()
+        .background(assets.image("window_background"))
+        .background_margin(RectOffset::new(32.0, 76.0, 44.0, 20.0))
+        .margin(RectOffset::new(0.0, -40.0, 0.0, 0.0))
+        .build();
+
+    Skin {
+        window_style,
+        button_style: button_style(assets, font, "button_background"),
+        label_style: label_style(assets, font, WHITE),
+        ..root_ui().default_skin()
+    }
+}
+
+//a bare window with no background or margins, for drawing over the others
+pub fn build_cover() -> Skin {
+    let window_style = root_ui()
+        .style_builder()
+        .color(Color::new(0.0, 0.0, 0.0, 0.0))
+        .build();
+    Skin {
+        window_style,
+        ..root_ui().default_skin()
+    }
+}
+
+//the same skin with buttons drawn pressed and labels lit up, for the widget
+//with keyboard focus
+pub fn build_focus(assets: &Assets, font: bool, skin: &Skin) -> Skin {
+    Skin {
+        button_style: button_style(assets, font, "button_clicked_background"),
+        label_style: label_style(assets, font, FOCUS_COLOR),
+        ..skin.clone()
+    }
+}
@@ -0,0 +1,126 @@
+use macroquad::prelude::*;
+
+use crate::config::{parse_color, Config, Section};
+use crate::starfield::StarfieldPreset;
+
+//one scrolling image behind the playfield
+pub struct LayerDesc {
+    pub texture: String,
+    //units per second the layer falls past the ship
+    pub speed: f32,
+    //how far it slides sideways with the ship's banking, 1.0 matches the stars
+    pub parallax: f32,
+    pub color: Color,
+}
+
+pub struct Stage {
+    //from the section header, shown on the hud
+    pub name: String,
+    //score that moves the run into this stage
+    pub score: u32,
+    pub layers: Vec<LayerDesc>,
+    pub starfield: StarfieldPreset,
+    //seconds of warp speed when the stage is entered
+    pub warp: f32,
+}
+
+//`stars_*` keys, anything left out keeps the shader's defaults
+fn starfield_preset(section: &Section) -> StarfieldPreset {
+    let defaults = StarfieldPreset::default();
+    let tint = section
+        .get("stars_tint")
+        .and_then(parse_color)
+        .map_or(defaults.tint, |c| vec3(c.r, c.g, c.b));
+    StarfieldPreset {
+        layers: section.get_f32("stars_layers", defaults.layers).clamp(1.0, 8.0),
+        speed: section.get_f32("stars_speed", defaults.speed),
+        tint,
+        twinkle: section.get_f32("stars_twinkle", defaults.twinkle),
+        drift: section.get_f32("stars_drift", defaults.drift),
+        travel: section.get_f32("stars_travel", defaults.travel),
+    }
+}
+
+//`[stage <name>]` sections, in the order they are reached
+pub fn load(text: &str) -> Vec<Stage> {
+    let config = Config::parse(text);
+    let mut stages = vec![];
+    for section in &config.sections {
+        let Some(name) = section.name.strip_prefix("stage ") else {
+            continue;
+        };
+        let mut layers = vec![];
+        for line in section.get_all("layer") {
+            let parts: Vec<&str> = line.split(',').map(str::trim).collect();
+            let number = |i: usize| parts.get(i).and_then(|v| v.parse().ok());
+            let (Some(speed), Some(parallax)) = (number(1), number(2)) else {
+                warn!("stage {}: `layer = {}` needs texture, speed, parallax", name, line);
+                continue;
+            };
+            layers.push(LayerDesc {
+                texture: parts[0].to_string(),
+                speed,
+                parallax,
+                color: parts.get(3).and_then(|c| parse_color(c)).unwrap_or(WHITE),
+            });
+        }
+        stages.push(Stage {
+            name: name.to_string(),
+            score: section.get_u32("score", 0),
+            layers,
+            starfield: starfield_preset(section),
+            warp: section.get_f32("warp", 0.0),
+        });
+    }
+
+    if stages.is_empty() {
+        stages.push(Stage {
+            name: "1".to_string(),
+            score: 0,
+            layers: vec![],
+            starfield: StarfieldPreset::default(),
+            warp: 0.0,
+        });
+    }
+    stages
+}
+
+pub fn stage_for(stages: &[Stage], score: u32) -> usize {
+    stages.iter().rposition(|s| score >= s.score).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_layers_and_tints() {
+        let stages = load(
+            "[stage one]\nlayer = dust, 90, 1.5, #ffffff80\nlayer = nebula, 8, 0.3, purple\n\
+             layer = rocks, 40\nstars_tint = #ff0000\n\
+             [stage two]\nscore = 500\nlayer = nebula, 8, 0.3\nstars_tint = red\n",
+        );
+        let defaults = StarfieldPreset::default();
+        let one = &stages[0];
+        //the layer missing its parallax is skipped, a bad colour falls back to white
+        assert_eq!(one.layers.len(), 2);
+        assert_eq!(one.layers[0].color.a, 128.0 / 255.0);
+        assert_eq!(one.layers[1].color, WHITE);
+        assert_eq!(one.starfield.tint, vec3(1.0, 0.0, 0.0));
+        assert_eq!(one.starfield.speed, defaults.speed);
+        let two = &stages[1];
+        assert_eq!(two.score, 500);
+        assert_eq!(two.layers[0].color, WHITE);
+        assert_eq!(two.starfield.tint, defaults.tint);
+        assert_eq!(stage_for(&stages, 499), 0);
+        assert_eq!(stage_for(&stages, 500), 1);
+    }
+
+    #[test]
+    fn no_stages_gives_one_plain_stage() {
+        let stages = load("# nothing here\n");
+        assert_eq!(stages.len(), 1);
+        assert!(stages[0].layers.is_empty());
+        assert_eq!(stages[0].starfield.tint, StarfieldPreset::default().tint);
+    }
+}
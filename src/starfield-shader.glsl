@@ -14,7 +14,16 @@ varying float iTime;
 uniform vec2 iResolution;
 uniform float direction_modifier;
 
-#define NUM_LAYERS 4.
+// driven from Rust, see starfield.rs
+uniform float num_layers;
+uniform float speed;
+uniform vec3 tint;
+uniform float drift;
+// accumulated on the CPU so changing rates never makes the stars jump
+uniform float travel;
+uniform float twinkle;
+
+#define MAX_LAYERS 8
 
 mat2 Rot(float a) {
     float s = sin(a), c = cos(a);
@@ -57,8 +66,8 @@ vec3 StarLayer(vec2 uv) {
             float size = fract(n * 345.32);
             float star = Star(gv - offs - vec2(n, fract(n * 42.)) + .5, smoothstep(.9, 1., size) * .6);
             vec3 color = sin(vec3(.8, .8, .8) * fract(n * 2345.2) * 123.2) * .5 + .5;
-            color = color * vec3(0.25, 0.25, 0.20);
-            star *= sin(iTime * 3. + n * 6.2831) * .5 + 1.;
+            color = color * tint;
+            star *= sin(twinkle + n * 6.2831) * .5 + 1.;
             col += star * size * color;
         }
     }
@@ -68,15 +77,18 @@ vec3 StarLayer(vec2 uv) {
 void main()
 {
     vec2 uv = (gl_FragCoord.xy - .5 * iResolution.xy) / iResolution.y;
-    float t = iTime * .02;
+    float t = travel * .02;
 
-    float speed = 3.0;
-    vec2 direction = vec2(-0.25 + direction_modifier, -1.0) * speed;
+    vec2 direction = vec2(drift + direction_modifier, -1.0) * speed;
 
     uv += direction;
     vec3 col = vec3(0);
 
-    for (float i = 0.; i < 1.; i += 1. / NUM_LAYERS) {
+    for (int layer = 0; layer < MAX_LAYERS; layer++) {
+        if (float(layer) >= num_layers) {
+            break;
+        }
+        float i = float(layer) / num_layers;
         float depth = fract(i+t);
         float scale = mix(20., .5, depth);
         float fade = depth * smoothstep(1., .9, depth);
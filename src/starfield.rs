@@ -0,0 +1,237 @@
+use macroquad::prelude::*;
+
+use crate::settings::Settings;
+use crate::shader::{self, ShaderFile, STARFIELD_FRAGMENT, STARFIELD_VERTEX};
+use crate::viewport;
+
+//how far the stars lean per second of steering, and how quickly they settle
+//back to center once the ship stops banking
+const STEER_RATE: f32 = 0.05;
+const STEER_SMOOTHING: f32 = 6.0;
+const STEER_DECAY: f32 = 0.25;
+//seconds for a new preset to mostly blend in
+const PRESET_FADE: f32 = 1.5;
+//travel rate multiplier at the peak of a warp
+const WARP_SPEED: f32 = 12.0;
+
+//the shader's look, blended between when a stage sets a new one
+#[derive(Clone, Copy, Debug)]
+pub struct StarfieldPreset {
+    pub layers: f32,
+    //how far steering and drift shift the field
+    pub speed: f32,
+    pub tint: Vec3,
+    //radians per second of the twinkle
+    pub twinkle: f32,
+    //constant sideways lean, negative drifts left
+    pub drift: f32,
+    //how fast the layers fly at the camera, 1.0 is the original pace
+    pub travel: f32,
+}
+
+impl Default for StarfieldPreset {
+    fn default() -> Self {
+        StarfieldPreset {
+            layers: 4.0,
+            speed: 3.0,
+            tint: vec3(0.25, 0.25, 0.20),
+            twinkle: 3.0,
+            drift: -0.25,
+            travel: 1.0,
+        }
+    }
+}
+
+impl StarfieldPreset {
+    fn lerp(&self, other: &StarfieldPreset, t: f32) -> StarfieldPreset {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        StarfieldPreset {
+            layers: mix(self.layers, other.layers),
+            speed: mix(self.speed, other.speed),
+            tint: self.tint.lerp(other.tint, t),
+            twinkle: mix(self.twinkle, other.twinkle),
+            drift: mix(self.drift, other.drift),
+            travel: mix(self.travel, other.travel),
+        }
+    }
+}
+
+//renders the starfield shader at a fixed low internal resolution and
+//upscales it with nearest filtering, so the pixel size and the fill cost stay
+//the same whatever the window size is
+pub struct Starfield {
+    render_target: RenderTarget,
+    //left empty when the shader fails to compile, drawing plain black instead
+    material: Option<Material>,
+    preset: StarfieldPreset,
+    target: StarfieldPreset,
+    //rates are integrated here rather than multiplied with the shader's
+    //time, which would jump whenever a rate changed
+    travel: f32,
+    twinkle: f32,
+    steer: f32,
+    steer_velocity: f32,
+    direction_modifier: f32,
+    warp_time: f32,
+    warp_length: f32,
+    //off with reduced motion, the stars keep whatever brightness they had
+    twinkling: bool,
+}
+
+impl Starfield {
+    pub fn new(settings: &Settings) -> Self {
+        let height = settings.background_height;
+        let material = Self::compile()
+            .inspect_err(|e| error!("starfield shader: {}", e))
+            .ok();
+
+        Starfield {
+            render_target: Self::target(height),
+            material,
+            preset: StarfieldPreset::default(),
+            target: StarfieldPreset::default(),
+            travel: 0.0,
+            twinkle: 0.0,
+            steer: 0.0,
+            steer_velocity: 0.0,
+            direction_modifier: 0.0,
+            warp_time: 0.0,
+            warp_length: 0.0,
+            twinkling: !settings.reduced_motion,
+        }
+    }
+
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        self.twinkling = !settings.reduced_motion;
+    }
+
+    //width follows the playfield's aspect ratio so the pixels stay square
+    fn target(height: u32) -> RenderTarget {
+        let width = (height as f32 * viewport::WIDTH / viewport::HEIGHT).round() as u32;
+        let render_target = render_target(width, height);
+        render_target.texture.set_filter(FilterMode::Nearest);
+        render_target
+    }
+
+    fn compile() -> Result<Material, String> {
+        shader::compile(
+            &STARFIELD_VERTEX,
+            &STARFIELD_FRAGMENT,
+            MaterialParams {
+                uniforms: vec![
+                    UniformDesc::new("iResolution", UniformType::Float2),
+                    UniformDesc::new("direction_modifier", UniformType::Float1),
+                    UniformDesc::new("num_layers", UniformType::Float1),
+                    UniformDesc::new("speed", UniformType::Float1),
+                    UniformDesc::new("tint", UniformType::Float3),
+                    UniformDesc::new("drift", UniformType::Float1),
+                    UniformDesc::new("travel", UniformType::Float1),
+                    UniformDesc::new("twinkle", UniformType::Float1),
+                ],
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn shader_files(&self) -> [&'static ShaderFile; 2] {
+        [&STARFIELD_VERTEX, &STARFIELD_FRAGMENT]
+    }
+
+    //keeps the previous material if the new source doesn't compile
+    pub fn reload(&mut self) -> Result<(), String> {
+        self.material = Some(Self::compile()?);
+        Ok(())
+    }
+
+    //blends towards the preset over a second or so instead of snapping
+    pub fn set_preset(&mut self, preset: &StarfieldPreset) {
+        self.target = *preset;
+    }
+
+    //rushes the stars past for a while, easing in and out
+    pub fn warp(&mut self, seconds: f32) {
+        self.warp_time = 0.0;
+        self.warp_length = seconds.max(0.0);
+    }
+
+    //-1.0 banks left, 1.0 right and 0.0 lets the field settle
+    pub fn steer(&mut self, input: f32) {
+        self.steer = input.clamp(-1.0, 1.0);
+    }
+
+    //sideways lean in units of the playfield's height, shared with the
+    //background layers so they slide along with the stars
+    pub fn direction_modifier(&self) -> f32 {
+        self.direction_modifier
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        let blend = 1.0 - (-delta_time * 2.0 / PRESET_FADE).exp();
+        self.preset = self.preset.lerp(&self.target, blend);
+
+        let smoothing = (delta_time * STEER_SMOOTHING).min(1.0);
+        self.steer_velocity += (self.steer * STEER_RATE - self.steer_velocity) * smoothing;
+        self.direction_modifier += self.steer_velocity * delta_time;
+        self.direction_modifier -= self.direction_modifier * (delta_time * STEER_DECAY).min(1.0);
+
+        let mut travel = self.preset.travel;
+        if self.warp_time < self.warp_length {
+            self.warp_time += delta_time;
+            let progress = (self.warp_time / self.warp_length).min(1.0);
+            travel *= 1.0 + (WARP_SPEED - 1.0) * (progress * std::f32::consts::PI).sin();
+        }
+        self.travel += travel * delta_time;
+        if self.twinkling {
+            self.twinkle = (self.twinkle + self.preset.twinkle * delta_time) % std::f32::consts::TAU;
+        }
+    }
+
+    //background pass into the low resolution target, done before anything
+    //else is drawn as it switches cameras
+    pub fn render(&self) {
+        let Some(material) = &self.material else {
+            return;
+        };
+
+        let size = self.render_target.texture.size();
+        let mut camera = Camera2D::from_display_rect(Rect::new(0.0, 0.0, size.x, size.y));
+        camera.render_target = Some(self.render_target.clone());
+        set_camera(&camera);
+        clear_background(BLACK);
+        material.set_uniform("iResolution", (size.x, size.y));
+        material.set_uniform("direction_modifier", self.direction_modifier);
+        material.set_uniform("num_layers", self.preset.layers);
+        material.set_uniform("speed", self.preset.speed);
+        material.set_uniform("tint", self.preset.tint);
+        material.set_uniform("drift", self.preset.drift);
+        material.set_uniform("travel", self.travel);
+        material.set_uniform("twinkle", self.twinkle);
+        gl_use_material(material);
+        draw_rectangle(0.0, 0.0, size.x, size.y, WHITE);
+        gl_use_default_material();
+        set_default_camera();
+    }
+
+    //the last rendered frame, stored bottom up like any render target
+    pub fn texture(&self) -> &Texture2D {
+        &self.render_target.texture
+    }
+
+    pub fn draw(&self, dest: Rect) {
+        if self.material.is_none() {
+            return;
+        }
+        //the target's rows are stored bottom up, flip to match the screen
+        draw_texture_ex(
+            &self.render_target.texture,
+            dest.x,
+            dest.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(dest.size()),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use macroquad::prelude::*;
+
+use crate::game::Game;
+use crate::viewport::Viewport;
+use crate::wipe::WipeStyle;
+
+//what a state asks the stack to do after its update
+pub enum Transition {
+    None,
+    //cover the current state, which stays on the stack untouched
+    Push(Box<dyn State>),
+    //back to the state underneath
+    Pop,
+    //swap the current state for another
+    Replace(Box<dyn State>),
+    //throw the whole stack away and start over from this state
+    Reset(Box<dyn State>),
+}
+
+pub trait State {
+    //pushed onto the stack, or made the current state
+    fn enter(&mut self, _game: &mut Game) {}
+    //about to leave the stack
+    fn exit(&mut self, _game: &mut Game) {}
+    //the overlay above was popped and this is the top state again
+    fn resume(&mut self, _game: &mut Game) {}
+
+    //only the top state updates, this is also where its ui windows go.
+    //`blocked` is set while a wipe runs: the windows are still built so the
+    //wipe can close over them, but nothing may react to input and whatever
+    //is returned is ignored
+    fn update(&mut self, game: &mut Game, blocked: bool) -> Transition;
+    //the playfield, in world units
+    fn draw(&mut self, _game: &mut Game) {}
+    //window space, over the scaled up playfield
+    fn draw_ui(&mut self, _game: &mut Game) {}
+
+    //overlays let the states below keep drawing underneath them
+    fn is_overlay(&self) -> bool {
+        false
+    }
+    //keep showing the last rendered playfield instead of redrawing it
+    fn freezes(&self) -> bool {
+        false
+    }
+    //how the screen changes over when this state is entered or popped,
+    //None switches straight away
+    fn wipe(&self) -> Option<WipeStyle> {
+        Some(WipeStyle::Fade)
+    }
+}
+
+pub struct StateStack {
+    states: Vec<Box<dyn State>>,
+    //waiting for the wipe to cover the screen
+    pending: Option<Transition>,
+}
+
+impl StateStack {
+    pub fn new(mut first: Box<dyn State>, game: &mut Game) -> Self {
+        first.enter(game);
+        StateStack {
+            states: vec![first],
+            pending: None,
+        }
+    }
+
+    //the top state is blocked while a wipe runs, so input is ignored until
+    //the new state is fully on screen
+    pub fn update(&mut self, game: &mut Game) {
+        if game.wipe.active() {
+            if game.wipe.update(get_frame_time())
+                && let Some(transition) = self.pending.take()
+            {
+                self.apply(transition, game);
+            }
+            if let Some(top) = self.states.last_mut() {
+                top.update(game, true);
+            }
+            return;
+        }
+        let Some(top) = self.states.last_mut() else {
+            return;
+        };
+        let transition = top.update(game, false);
+        let style = match &transition {
+            Transition::None => return,
+            Transition::Pop => top.wipe(),
+            Transition::Push(state) | Transition::Replace(state) | Transition::Reset(state) => {
+                state.wipe()
+            }
+        };
+        let seconds = game.settings.transition_time;
+        match style {
+            Some(style) if seconds > 0.0 => {
+                if style == WipeStyle::Warp {
+                    game.starfield.warp(seconds);
+                }
+                game.wipe.start(style, seconds);
+                self.pending = Some(transition);
+            }
+            _ => self.apply(transition, game),
+        }
+    }
+
+    fn apply(&mut self, transition: Transition, game: &mut Game) {
+        match transition {
+            Transition::None => {}
+            Transition::Push(mut state) => {
+                state.enter(game);
+                self.states.push(state);
+            }
+            Transition::Pop => {
+                if let Some(mut state) = self.states.pop() {
+                    state.exit(game);
+                }
+                if let Some(top) = self.states.last_mut() {
+                    top.resume(game);
+                }
+            }
+            Transition::Replace(mut state) => {
+                if let Some(mut old) = self.states.pop() {
+                    old.exit(game);
+                }
+                state.enter(game);
+                self.states.push(state);
+            }
+            Transition::Reset(mut state) => {
+                while let Some(mut old) = self.states.pop() {
+                    old.exit(game);
+                }
+                state.enter(game);
+                self.states.push(state);
+            }
+        }
+    }
+
+    pub fn frozen(&self) -> bool {
+        self.states.last().is_some_and(|s| s.freezes())
+    }
+
+    //the top state and every overlay down to the first opaque state
+    fn visible(&self) -> usize {
+        self.states
+            .iter()
+            .rposition(|s| !s.is_overlay())
+            .unwrap_or(0)
+    }
+
+    pub fn draw(&mut self, game: &mut Game) {
+        let first = self.visible();
+        for state in &mut self.states[first..] {
+            state.draw(game);
+        }
+    }
+
+    //the wipe goes over everything, the ui windows included
+    pub fn draw_ui(&mut self, game: &mut Game) {
+        let first = self.visible();
+        for state in &mut self.states[first..] {
+            state.draw_ui(game);
+        }
+        game.wipe.draw(Viewport::screen_rect(), game.starfield.texture());
+    }
+}
@@ -0,0 +1,74 @@
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+use crate::game::{centered, Game};
+use crate::nav::Nav;
+use crate::palette::Palette;
+use crate::state::{State, Transition};
+use crate::wipe::WipeStyle;
+
+const WINDOW_SIZE: Vec2 = vec2(560.0, 420.0);
+
+//colours, motion, contrast and game speed, opened from the options
+pub struct Accessibility {
+    nav: Nav,
+}
+
+impl Accessibility {
+    pub fn new() -> Self {
+        Accessibility { nav: Nav::new() }
+    }
+}
+
+impl State for Accessibility {
+    fn exit(&mut self, game: &mut Game) {
+        game.settings.save();
+    }
+
+    fn update(&mut self, game: &mut Game, blocked: bool) -> Transition {
+        let mut back = is_key_pressed(KeyCode::Escape);
+        let settings = &mut game.settings;
+        let nav = &mut self.nav;
+        let focus = &game.focus_skin;
+        let text = &game.locale;
+        let palettes: Vec<&str> = Palette::ALL
+            .iter()
+            .map(|p| text.get_or(&format!("palette.{}", p.key()), p.key()))
+            .collect();
+        let mut palette = Palette::ALL
+            .iter()
+            .position(|p| *p == settings.palette)
+            .unwrap_or(0);
+        nav.begin(&game.gamepad, blocked);
+        root_ui().window(hash!(), centered(WINDOW_SIZE), WINDOW_SIZE, |ui| {
+            ui.label(vec2(100.0, -34.0), text.get("accessibility.title"));
+            nav.combo_box(ui, focus, hash!(), text.get("accessibility.palette"), &palettes, &mut palette);
+            nav.checkbox(ui, focus, hash!(), text.get("accessibility.reduced_motion"), &mut settings.reduced_motion);
+            nav.checkbox(ui, focus, hash!(), text.get("accessibility.high_contrast"), &mut settings.high_contrast);
+            nav.slider(ui, focus, hash!(), text.get("accessibility.game_speed"), 0.25..1.0, &mut settings.game_speed);
+            if nav.button(ui, focus, None, text.get("options.back")) {
+                back = true;
+            }
+        });
+        settings.palette = Palette::ALL[palette];
+        game.apply_settings();
+
+        if back {
+            Transition::Pop
+        } else {
+            Transition::None
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    fn freezes(&self) -> bool {
+        true
+    }
+
+    fn wipe(&self) -> Option<WipeStyle> {
+        None
+    }
+}
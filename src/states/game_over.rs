@@ -0,0 +1,107 @@
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+use crate::difficulty::Difficulty;
+use crate::game::{centered, dim, Game};
+use crate::nav::Nav;
+use crate::state::{State, Transition};
+use crate::states::{MainMenu, Playing};
+use crate::stats::{self, RunStats};
+use crate::viewport::Viewport;
+use crate::wipe::WipeStyle;
+
+const WINDOW_SIZE: Vec2 = vec2(620.0, 640.0);
+
+pub struct GameOver {
+    run: RunStats,
+    score: u32,
+    difficulty: Difficulty,
+    gitgud: bool,
+    nav: Nav,
+}
+
+impl GameOver {
+    pub fn new(run: RunStats, score: u32, difficulty: Difficulty, gitgud: bool) -> Self {
+        GameOver {
+            run,
+            score,
+            difficulty,
+            gitgud,
+            nav: Nav::new(),
+        }
+    }
+}
+
+impl State for GameOver {
+    fn enter(&mut self, game: &mut Game) {
+        game.music.cue("game_over");
+        game.lifetime.add(&self.run);
+        game.lifetime.save();
+    }
+
+    fn update(&mut self, game: &mut Game, blocked: bool) -> Transition {
+        let run = &self.run;
+        let lifetime = &game.lifetime;
+        let text = &game.locale;
+        let mut lines = vec![];
+        for (i, enemy) in game.enemy_defs.iter().enumerate() {
+            let kills = run.kills.get(i).copied().unwrap_or(0);
+            let points = run.points.get(i).copied().unwrap_or(0);
+            let name = text.get_or(&format!("enemy.{}", enemy.name), &enemy.name);
+            lines.push(format!("{:<7} x{:<3} {:>5}", name, kills, text.number(points)));
+        }
+        lines.push(format!("{:<12} {:>5}", text.get("results.score"), text.number(self.score)));
+        let difficulty = format!("difficulty.{}", self.difficulty.preset.key());
+        let difficulty = text.get(&difficulty);
+        let key = if self.difficulty.dynamic { "results.difficulty_dynamic" } else { "results.difficulty" };
+        lines.push(text.format(key, &[("difficulty", &difficulty)]));
+        if self.gitgud {
+            lines.push(text.get("results.new_high_score").to_string());
+        }
+        let accuracy = stats::accuracy(run.shots, run.hits);
+        lines.push(text.format("results.shots", &[("shots", &run.shots), ("accuracy", &accuracy)]));
+        lines.push(text.format("results.time", &[("time", &stats::format_time(run.time))]));
+        lines.push(text.format("results.max_combo", &[("combo", &run.max_combo)]));
+        lines.push(String::new());
+        lines.push(text.plural("results.runs", lifetime.runs, &[]));
+        let accuracy = stats::accuracy(lifetime.shots, lifetime.hits);
+        let kills = text.number(lifetime.kills);
+        lines.push(text.format("results.kills", &[("kills", &kills), ("accuracy", &accuracy)]));
+        lines.push(text.format("results.played", &[("time", &stats::format_time(lifetime.time))]));
+        lines.push(text.format("results.best_combo", &[("combo", &lifetime.best_combo)]));
+
+        let (mut retry, mut menu) = (false, false);
+        let nav = &mut self.nav;
+        let focus = &game.focus_skin;
+        nav.begin(&game.gamepad, blocked);
+        root_ui().window(hash!(), centered(WINDOW_SIZE), WINDOW_SIZE, |ui| {
+            ui.label(vec2(190.0, -34.0), text.get("results.title"));
+            for (i, line) in lines.iter().enumerate() {
+                ui.label(vec2(30.0, 10.0 + i as f32 * 30.0), line);
+            }
+            let buttons = 30.0 + lines.len() as f32 * 30.0;
+            if nav.button(ui, focus, vec2(40.0, buttons), text.get("results.retry")) {
+                retry = true;
+            }
+            if nav.button(ui, focus, vec2(340.0, buttons), text.get("results.menu")) {
+                menu = true;
+            }
+        });
+
+        if retry {
+            Transition::Replace(Box::new(Playing::new(game)))
+        } else if menu {
+            Transition::Replace(Box::new(MainMenu::new()))
+        } else {
+            Transition::None
+        }
+    }
+
+    fn draw_ui(&mut self, _game: &mut Game) {
+        dim(Viewport::screen_rect());
+    }
+
+    fn wipe(&self) -> Option<WipeStyle> {
+        Some(WipeStyle::Dissolve)
+    }
+}
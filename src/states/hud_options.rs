@@ -0,0 +1,76 @@
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+use crate::game::{centered, Game};
+use crate::hud::{Anchor, Element};
+use crate::nav::Nav;
+use crate::state::{State, Transition};
+use crate::wipe::WipeStyle;
+
+const WINDOW_SIZE: Vec2 = vec2(560.0, 520.0);
+
+//picks where each hud item sits, or hides it, opened from the options
+pub struct HudOptions {
+    nav: Nav,
+}
+
+impl HudOptions {
+    pub fn new() -> Self {
+        HudOptions { nav: Nav::new() }
+    }
+}
+
+impl State for HudOptions {
+    fn exit(&mut self, game: &mut Game) {
+        game.settings.save();
+    }
+
+    fn update(&mut self, game: &mut Game, blocked: bool) -> Transition {
+        let mut back = is_key_pressed(KeyCode::Escape);
+        let layout = &mut game.settings.hud;
+        let nav = &mut self.nav;
+        let focus = &game.focus_skin;
+        nav.begin(&game.gamepad, blocked);
+        //the first choice hides the item
+        let text = &game.locale;
+        let choices: Vec<&str> = std::iter::once(text.get("hud_options.off"))
+            .chain(Anchor::ALL.iter().map(|a| text.get_or(&format!("anchor.{}", a.key()), a.key())))
+            .collect();
+        root_ui().window(hash!(), centered(WINDOW_SIZE), WINDOW_SIZE, |ui| {
+            ui.label(vec2(200.0, -34.0), text.get("hud_options.title"));
+            for (i, element) in Element::ALL.into_iter().enumerate() {
+                let current = layout
+                    .get(element)
+                    .and_then(|p| Anchor::ALL.iter().position(|a| *a == p.anchor))
+                    .map_or(0, |i| i + 1);
+                let mut choice = current;
+                let label = text.get_or(&format!("element.{}", element.key()), element.key());
+                nav.combo_box(ui, focus, hash!("hud", i), label, &choices, &mut choice);
+                if choice != current {
+                    layout.set_anchor(element, choice.checked_sub(1).map(|i| Anchor::ALL[i]));
+                }
+            }
+            if nav.button(ui, focus, None, text.get("options.back")) {
+                back = true;
+            }
+        });
+
+        if back {
+            Transition::Pop
+        } else {
+            Transition::None
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    fn freezes(&self) -> bool {
+        true
+    }
+
+    fn wipe(&self) -> Option<WipeStyle> {
+        None
+    }
+}
@@ -0,0 +1,74 @@
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+use crate::game::{centered, Game};
+use crate::nav::Nav;
+use crate::state::{State, Transition};
+use crate::states::Playing;
+use crate::wipe::WipeStyle;
+
+const WINDOW_SIZE: Vec2 = vec2(620.0, 520.0);
+
+pub struct MainMenu {
+    nav: Nav,
+}
+
+impl MainMenu {
+    pub fn new() -> Self {
+        MainMenu { nav: Nav::new() }
+    }
+}
+
+impl State for MainMenu {
+    fn enter(&mut self, game: &mut Game) {
+        game.music.cue("menu");
+    }
+
+    fn update(&mut self, game: &mut Game, blocked: bool) -> Transition {
+        let (mut play, mut preset, mut dynamic) = (false, false, false);
+        let difficulty = game.settings.difficulty;
+        let nav = &mut self.nav;
+        let focus = &game.focus_skin;
+        let text = &game.locale;
+        let preset_name = format!("difficulty.{}", difficulty.preset.key());
+        let mode = if difficulty.dynamic { "difficulty.dynamic" } else { "difficulty.fixed" };
+        nav.begin(&game.gamepad, blocked);
+        root_ui().window(hash!(), centered(WINDOW_SIZE), WINDOW_SIZE, |ui| {
+            ui.label(vec2(205.0, -34.0), text.get("menu.title"));
+            if nav.button(ui, focus, vec2(190.0, 25.0), text.get("menu.play")) {
+                play = true;
+            }
+            //each press steps to the next choice
+            ui.label(vec2(30.0, 155.0), text.get("menu.difficulty"));
+            if nav.button(ui, focus, vec2(260.0, 125.0), text.get(&preset_name)) {
+                preset = true;
+            }
+            ui.label(vec2(30.0, 255.0), text.get("menu.pressure"));
+            if nav.button(ui, focus, vec2(260.0, 225.0), text.get(mode)) {
+                dynamic = true;
+            }
+            if nav.button(ui, focus, vec2(190.0, 325.0), text.get("menu.quit")) {
+                std::process::exit(0);
+            }
+        });
+
+        if preset || dynamic {
+            let difficulty = &mut game.settings.difficulty;
+            if preset {
+                difficulty.preset = difficulty.preset.next();
+            }
+            difficulty.dynamic ^= dynamic;
+            game.settings.save();
+        }
+
+        if play {
+            Transition::Replace(Box::new(Playing::new(game)))
+        } else {
+            Transition::None
+        }
+    }
+
+    fn wipe(&self) -> Option<WipeStyle> {
+        Some(WipeStyle::Iris)
+    }
+}
@@ -0,0 +1,15 @@
+mod accessibility;
+mod game_over;
+mod hud_options;
+mod main_menu;
+mod options;
+mod pause;
+mod playing;
+
+pub use accessibility::Accessibility;
+pub use game_over::GameOver;
+pub use hud_options::HudOptions;
+pub use main_menu::MainMenu;
+pub use options::Options;
+pub use pause::Pause;
+pub use playing::Playing;
@@ -0,0 +1,93 @@
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+use crate::game::{centered, Game};
+use crate::nav::Nav;
+use crate::state::{State, Transition};
+use crate::states::{Accessibility, HudOptions};
+use crate::wipe::WipeStyle;
+
+const WINDOW_SIZE: Vec2 = vec2(560.0, 640.0);
+
+//opened from the pause menu, over the same frozen playfield
+pub struct Options {
+    nav: Nav,
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Options { nav: Nav::new() }
+    }
+}
+
+impl State for Options {
+    //changes are heard and seen straight away, saved on the way out
+    fn exit(&mut self, game: &mut Game) {
+        game.settings.save();
+    }
+
+    fn update(&mut self, game: &mut Game, blocked: bool) -> Transition {
+        let mut back = is_key_pressed(KeyCode::Escape);
+        let mut hud = false;
+        let mut accessibility = false;
+        let settings = &mut game.settings;
+        let nav = &mut self.nav;
+        let focus = &game.focus_skin;
+        let text = &game.locale;
+        let (codes, names): (Vec<&str>, Vec<&str>) = text.languages().unzip();
+        let current = codes.iter().position(|c| *c == text.code()).unwrap_or(0);
+        let mut language = current;
+        nav.begin(&game.gamepad, blocked);
+        root_ui().window(hash!(), centered(WINDOW_SIZE), WINDOW_SIZE, |ui| {
+            ui.label(vec2(150.0, -34.0), text.get("options.title"));
+            nav.slider(ui, focus, hash!(), text.get("options.volume"), 0.0..1.0, &mut settings.master_volume);
+            nav.slider(ui, focus, hash!(), text.get("options.music"), 0.0..1.0, &mut settings.music_volume);
+            nav.slider(ui, focus, hash!(), text.get("options.effects"), 0.0..1.0, &mut settings.sfx_volume);
+            nav.slider(ui, focus, hash!(), text.get("options.shake"), 0.0..1.0, &mut settings.shake_intensity);
+            nav.slider(ui, focus, hash!(), text.get("options.flashes"), 0.0..1.0, &mut settings.flash_intensity);
+            nav.slider(ui, focus, hash!(), text.get("options.transitions"), 0.0..2.0, &mut settings.transition_time);
+            nav.checkbox(ui, focus, hash!(), text.get("options.bloom"), &mut settings.bloom);
+            nav.checkbox(ui, focus, hash!(), text.get("options.aberration"), &mut settings.chromatic_aberration);
+            nav.checkbox(ui, focus, hash!(), text.get("options.vignette"), &mut settings.vignette);
+            nav.checkbox(ui, focus, hash!(), text.get("options.crt"), &mut settings.crt);
+            nav.checkbox(ui, focus, hash!(), text.get("options.auto_pause"), &mut settings.auto_pause);
+            nav.combo_box(ui, focus, hash!(), text.get("options.language"), &names, &mut language);
+            if nav.button(ui, focus, None, text.get("options.hud")) {
+                hud = true;
+            }
+            if nav.button(ui, focus, None, text.get("options.accessibility")) {
+                accessibility = true;
+            }
+            if nav.button(ui, focus, None, text.get("options.back")) {
+                back = true;
+            }
+        });
+        if language != current {
+            let code = codes[language].to_string();
+            game.set_language(&code);
+        }
+        game.apply_settings();
+
+        if hud {
+            Transition::Push(Box::new(HudOptions::new()))
+        } else if accessibility {
+            Transition::Push(Box::new(Accessibility::new()))
+        } else if back {
+            Transition::Pop
+        } else {
+            Transition::None
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    fn freezes(&self) -> bool {
+        true
+    }
+
+    fn wipe(&self) -> Option<WipeStyle> {
+        None
+    }
+}
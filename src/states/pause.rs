@@ -0,0 +1,95 @@
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+use crate::game::{centered, dim, Game};
+use crate::nav::Nav;
+use crate::state::{State, Transition};
+use crate::states::{MainMenu, Options, Playing};
+use crate::viewport::Viewport;
+use crate::wipe::WipeStyle;
+
+const WINDOW_SIZE: Vec2 = vec2(370.0, 520.0);
+
+pub struct Pause {
+    //paused because the window was left rather than by the player
+    auto: bool,
+    nav: Nav,
+}
+
+#[derive(Clone, Copy)]
+enum Choice {
+    Resume,
+    Restart,
+    Options,
+    Menu,
+}
+
+impl Pause {
+    pub fn new(auto: bool) -> Self {
+        Pause {
+            auto,
+            nav: Nav::new(),
+        }
+    }
+}
+
+impl State for Pause {
+    fn enter(&mut self, game: &mut Game) {
+        game.music.cue("paused");
+        game.audio.set_away(self.auto);
+    }
+
+    fn exit(&mut self, game: &mut Game) {
+        game.audio.set_away(false);
+    }
+
+    fn update(&mut self, game: &mut Game, blocked: bool) -> Transition {
+        let mut choice = is_key_pressed(KeyCode::Escape).then_some(Choice::Resume);
+        let nav = &mut self.nav;
+        let focus = &game.focus_skin;
+        let text = &game.locale;
+        nav.begin(&game.gamepad, blocked);
+        root_ui().window(hash!(), centered(WINDOW_SIZE), WINDOW_SIZE, |ui| {
+            ui.label(vec2(100.0, -34.0), text.get("pause.title"));
+            let buttons = [
+                (Choice::Resume, "pause.resume"),
+                (Choice::Restart, "pause.restart"),
+                (Choice::Options, "pause.options"),
+                (Choice::Menu, "pause.menu"),
+            ];
+            for (i, (option, label)) in buttons.into_iter().enumerate() {
+                if nav.button(ui, focus, vec2(65.0, 25.0 + i as f32 * 100.0), text.get(label)) {
+                    choice = Some(option);
+                }
+            }
+        });
+
+        match choice {
+            None => Transition::None,
+            Some(Choice::Resume) => Transition::Pop,
+            Some(Choice::Restart) => Transition::Reset(Box::new(Playing::new(game))),
+            Some(Choice::Options) => Transition::Push(Box::new(Options::new())),
+            Some(Choice::Menu) => Transition::Reset(Box::new(MainMenu::new())),
+        }
+    }
+
+    //the menu window is drawn over this by the ui at the end of the frame
+    fn draw_ui(&mut self, _game: &mut Game) {
+        dim(Viewport::screen_rect());
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    //the playfield target is left alone, so it keeps showing the frame the
+    //game was paused on
+    fn freezes(&self) -> bool {
+        true
+    }
+
+    //pausing and resuming cut straight over
+    fn wipe(&self) -> Option<WipeStyle> {
+        None
+    }
+}
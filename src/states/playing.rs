@@ -0,0 +1,378 @@
+use macroquad::prelude::*;
+
+use crate::audio::screen_pan;
+use crate::difficulty::{Difficulty, Director};
+use crate::enemies;
+use crate::game::Game;
+use crate::hud::HudData;
+use crate::impact::Impact;
+use crate::palette::Palette;
+use crate::state::{State, Transition};
+use crate::states::{GameOver, Pause};
+use crate::stats::RunStats;
+use crate::stages;
+use crate::viewport;
+use crate::wipe::WipeStyle;
+
+const MOVEMENT_SPEED: f32 = 200.0;
+const BOOST: f32 = 1.6;
+//odds of an enemy appearing each frame at full game speed on normal
+const SPAWN_CHANCE: f32 = 0.04;
+const LIVES: u32 = 3;
+//run time after a hit before the next one can land
+const INVULNERABLE: f32 = 1.5;
+
+struct Shape {
+    size: f32,
+    speed: f32,
+    x: f32,
+    y: f32,
+    collided: bool,
+    //hits left
+    health: u32,
+}
+
+impl Shape {
+    fn collides_with(&self, other: &Self) -> bool {
+        self.rect().overlaps(&other.rect())
+    }
+
+    fn rect(&self) -> Rect {
+        Rect {
+            x: self.x - self.size / 2.0,
+            y: self.y - self.size / 2.0,
+            w: self.size,
+            h: self.size,
+        }
+    }
+}
+
+//one run, from the first frame to the collision that ends it
+pub struct Playing {
+    //track enemies and bullets
+    squares: Vec<Shape>,
+    bullets: Vec<Shape>,
+    circle: Shape,
+    //in run time, which the game speed slows down
+    last_shot: f32,
+    //in run time too, the ship blinks until then
+    safe_until: f32,
+    run: RunStats,
+    //fixed for the run, changing it only applies to the next one
+    difficulty: Difficulty,
+    director: Director,
+}
+
+impl Playing {
+    pub fn new(game: &Game) -> Self {
+        let difficulty = game.settings.difficulty;
+        Playing {
+            squares: vec![],
+            bullets: vec![],
+            //set up the player shape
+            circle: Shape {
+                size: 32.0,
+                speed: MOVEMENT_SPEED,
+                x: viewport::WIDTH / 2.0,
+                y: viewport::HEIGHT / 2.0,
+                collided: false,
+                health: LIVES,
+            },
+            last_shot: 0.0,
+            safe_until: 0.0,
+            run: RunStats::new(game.enemy_defs.len()),
+            difficulty,
+            director: Director::new(difficulty.dynamic),
+        }
+    }
+}
+
+impl State for Playing {
+    //the shared score is only reset once the run is switched to, the state
+    //being replaced may still be on screen until then
+    fn enter(&mut self, game: &mut Game) {
+        game.score = 0;
+        game.high_score = game.high_scores.get(&self.difficulty.key());
+        game.effect_pool.clear();
+        game.music.cue("playing");
+    }
+
+    fn resume(&mut self, game: &mut Game) {
+        game.music.cue("playing");
+    }
+
+    fn update(&mut self, game: &mut Game, blocked: bool) -> Transition {
+        if blocked {
+            return Transition::None;
+        }
+        //checked before anything moves, so the long frame that gave the
+        //window away isn't simulated in one step
+        if game.settings.auto_pause && game.window_focus.lost() {
+            return Transition::Push(Box::new(Pause::new(true)));
+        }
+        let mut transition = Transition::None;
+        let circle = &mut self.circle;
+
+        //get player input
+        game.ship_sprite.set_animation(0);
+        //stands still during hit-stop and slows down with the game speed
+        let delta_time = get_frame_time() * game.impacts.time_scale() * game.settings.game_speed;
+        let now = self.run.time;
+        let boosting = is_key_down(KeyCode::LeftShift);
+        let speed = if boosting { circle.speed * BOOST } else { circle.speed };
+        if is_key_down(KeyCode::Right) {
+            circle.x += speed * delta_time;
+            game.starfield.steer(1.0);
+            game.ship_sprite.set_animation(4);
+        }
+        if is_key_down(KeyCode::Left) {
+            circle.x -= speed * delta_time;
+            game.starfield.steer(-1.0);
+            game.ship_sprite.set_animation(2);
+        }
+        if is_key_down(KeyCode::Down) {
+            circle.y += speed * delta_time;
+        }
+        if is_key_down(KeyCode::Up) {
+            circle.y -= speed * delta_time;
+        }
+        let axis = |negative, positive| {
+            is_key_down(positive) as i32 as f32 - is_key_down(negative) as i32 as f32
+        };
+        game.exhaust.update(
+            delta_time,
+            axis(KeyCode::Down, KeyCode::Up),
+            axis(KeyCode::Left, KeyCode::Right),
+            boosting,
+        );
+
+        if is_key_pressed(KeyCode::Escape) {
+            transition = Transition::Push(Box::new(Pause::new(false)));
+        }
+
+        // prevent player from moving off screen
+        circle.x = clamp(circle.x, circle.size / 2.0, viewport::WIDTH - (circle.size / 2.0));
+        circle.y = clamp(circle.y, circle.size / 2.0, viewport::HEIGHT - (circle.size / 2.0));
+
+        let scale = self.difficulty.preset.scale();
+        let pressure = self.director.pressure();
+
+        //shot
+        let weapon = &game.weapon_defs[0];
+        if delta_time > 0.0
+            && is_key_pressed(KeyCode::Space)
+            && now - self.last_shot > weapon.cooldown * scale.cooldown
+        {
+            self.bullets.push(Shape {
+                x: circle.x,
+                y: circle.y - 24.0,
+                speed: circle.speed * weapon.speed,
+                size: 32.0,
+                collided: false,
+                health: 1,
+            });
+            game.audio.play(&weapon.sound, screen_pan(circle.x), 1.0);
+            self.run.shot();
+            if let Some(muzzle) = &weapon.muzzle {
+                game.effect_pool.spawn(&game.effects, muzzle, vec2(circle.x, circle.y - 24.0), 1.0);
+            }
+            self.last_shot = now;
+        }
+
+        //create randomly sized squares
+        let spawn_chance = SPAWN_CHANCE * game.settings.game_speed * scale.spawn * pressure;
+        if delta_time > 0.0 && rand::gen_range(0.0, 1.0) < spawn_chance {
+            let size = rand::gen_range(16.0, 64.0);
+            self.squares.push(Shape {
+                size,
+                speed: rand::gen_range(50.0, 150.0) * scale.enemy_speed * pressure,
+                x: rand::gen_range(size / 2.0, viewport::WIDTH - size / 2.0),
+                y: -size,
+                collided: false,
+                health: scale.enemy_health,
+            });
+        }
+
+        self.run.update(delta_time);
+        self.director.update(delta_time);
+
+        //move non player objects
+        for square in &mut self.squares {
+            square.y += square.speed * delta_time;
+        }
+        for bullet in &mut self.bullets {
+            bullet.y -= bullet.speed * delta_time;
+        }
+
+        //sprites and particles keep their own clocks on the real frame time,
+        //so they're exempt from the game speed and hit-stop
+        game.ship_sprite.update();
+        game.bullet_sprite.update();
+        for sprite in &mut game.enemy_sprites {
+            sprite.update();
+        }
+
+        //remove nonplayer objects if they have left the screen
+        self.squares.retain(|square| square.y < viewport::HEIGHT + square.size);
+        self.bullets.retain(|bullet| bullet.y < viewport::HEIGHT + bullet.size);
+
+        //remove 'dead' objects
+        self.squares.retain(|square| !square.collided);
+        self.bullets.retain(|bullet| !bullet.collided);
+
+        //a crowded screen pushes the music harder
+        game.music.set_intensity(self.squares.len() as f32 / 20.0);
+
+        let mut dead = false;
+        if now >= self.safe_until
+            && let Some(square) = self.squares.iter_mut().find(|square| circle.collides_with(square))
+        {
+            //the enemy goes down with the ship's life so it can't land twice
+            square.collided = true;
+            let kind = enemies::for_size(&game.enemy_defs, square.size);
+            game.effect_pool.spawn(&game.effects, &game.enemy_defs[kind].explosion, vec2(square.x, square.y), square.size / 32.0);
+            circle.health -= 1;
+            dead = circle.health == 0;
+            self.safe_until = now + INVULNERABLE;
+            game.impacts.trigger(Impact::Damage);
+            game.exhaust.hit();
+            game.postfx.hit(1.0);
+        }
+        let gitgud = dead && game.score == game.high_score;
+        if gitgud {
+            game.high_scores.set(&self.difficulty.key(), game.score);
+            game.high_scores.save();
+        }
+
+        for square in self.squares.iter_mut() {
+            for bullet in self.bullets.iter_mut() {
+                if square.collided {
+                    break;
+                }
+                if bullet.collides_with(square) {
+                    //one bullet can take out two overlapping enemies
+                    if !bullet.collided {
+                        self.run.hit();
+                    }
+                    bullet.collided = true;
+                    square.health = square.health.saturating_sub(1);
+                    if square.health > 0 {
+                        if let Some(hit) = &game.weapon_defs[0].hit {
+                            game.effect_pool.spawn(&game.effects, hit, vec2(bullet.x, bullet.y), 1.0);
+                        }
+                        continue;
+                    }
+                    square.collided = true;
+                    self.director.kill();
+                    let points = square.size.round() as u32 / 2 * 2;
+                    game.score += points;
+                    game.high_score = game.high_score.max(game.score);
+                    let kind = enemies::for_size(&game.enemy_defs, square.size);
+                    self.run.kill(kind, points);
+                    let enemy = &game.enemy_defs[kind];
+                    game.effect_pool.spawn(&game.effects, &enemy.explosion, vec2(square.x, square.y), square.size / 32.0);
+                    if let Some(hit) = &game.weapon_defs[0].hit {
+                        game.effect_pool.spawn(&game.effects, hit, vec2(bullet.x, bullet.y), 1.0);
+                    }
+                    //bigger enemies go out with a bigger bang
+                    let volume = 0.4 + 0.6 * (square.size - 16.0) / 48.0;
+                    game.audio.play("explosion", screen_pan(square.x), volume);
+                    game.impacts.trigger(Impact::Kill { size: square.size });
+                }
+            }
+        }
+
+        if dead {
+            let run = std::mem::replace(&mut self.run, RunStats::new(0));
+            transition = Transition::Replace(Box::new(GameOver::new(run, game.score, self.difficulty, gitgud)));
+        }
+        transition
+    }
+
+    fn draw(&mut self, game: &mut Game) {
+        let palette = game.settings.palette;
+        let rim = |color| game.settings.high_contrast.then_some(color);
+        //a colour blind palette always marks out the enemies, high contrast
+        //outlines everything
+        let enemy_rim = (game.settings.high_contrast || palette != Palette::Default)
+            .then_some(palette.enemy());
+        let circle = &self.circle;
+        let circle_pos = vec2(circle.x, circle.y - (circle.size / 2.0));
+        game.exhaust.draw(circle_pos);
+        let blink = self.run.time < self.safe_until && (self.run.time * 10.0) as u32 % 2 == 1;
+
+        //the exhaust stays lit so the damage tint shows through the blink
+        if !blink {
+            let ship_frame = game.ship_sprite.frame();
+            game.outline.draw(
+                &game.ship_texture,
+                circle.x - ship_frame.dest_size.x,
+                circle.y - ship_frame.dest_size.y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(ship_frame.dest_size * 2.0),
+                    source: Some(ship_frame.source_rect),
+                    ..Default::default()
+                },
+                rim(palette.player()),
+            );
+        }
+
+        for square in &self.squares {
+            let kind = enemies::for_size(&game.enemy_defs, square.size);
+            game.outline.draw(
+                &game.enemy_textures[kind],
+                square.x - square.size / 2.0,
+                square.y - square.size / 2.0,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(square.size, square.size)),
+                    source: Some(game.enemy_sprites[kind].frame().source_rect),
+                    ..Default::default()
+                },
+                enemy_rim,
+            );
+        }
+
+        game.effect_pool.draw();
+
+        let bullet_frame = game.bullet_sprite.frame();
+        for bullet in &self.bullets {
+            game.outline.draw(
+                &game.bullet_texture,
+                bullet.x - bullet.size / 2.0,
+                bullet.y - bullet.size / 2.0,
+                palette.player_projectile(),
+                DrawTextureParams {
+                    dest_size: Some(vec2(bullet.size, bullet.size)),
+                    source: Some(bullet_frame.source_rect),
+                    ..Default::default()
+                },
+                rim(palette.player()),
+            );
+        }
+    }
+
+    fn draw_ui(&mut self, game: &mut Game) {
+        let stage = stages::stage_for(&game.stages, game.score);
+        let weapon = &game.weapon_defs[0];
+        game.hud.draw(
+            &game.settings.hud,
+            &HudData {
+                score: game.score,
+                high_score: game.high_score,
+                stage: &game.stages[stage].name,
+                combo: self.run.combo,
+                combo_left: self.run.combo_left(),
+                weapon: game.locale.get_or(&format!("weapon.{}", weapon.name), &weapon.name),
+                lives: self.circle.health,
+            },
+            &game.locale,
+        );
+    }
+
+    //flies into the run through the stars
+    fn wipe(&self) -> Option<WipeStyle> {
+        Some(WipeStyle::Warp)
+    }
+}
@@ -0,0 +1,183 @@
+use std::fs;
+
+use crate::config::Config;
+
+const LIFETIME_PATH: &str = "stats.dat";
+const HIGH_SCORE_PATH: &str = "highscore.dat";
+//seconds between kills that keeps a combo going
+const COMBO_WINDOW: f32 = 2.0;
+
+//what happened during one run, shown on the results screen
+pub struct RunStats {
+    //indexed like the enemy definitions
+    pub kills: Vec<u32>,
+    pub points: Vec<u32>,
+    pub shots: u32,
+    pub hits: u32,
+    pub time: f32,
+    pub combo: u32,
+    pub max_combo: u32,
+    since_kill: f32,
+}
+
+impl RunStats {
+    pub fn new(enemy_types: usize) -> Self {
+        RunStats {
+            kills: vec![0; enemy_types],
+            points: vec![0; enemy_types],
+            shots: 0,
+            hits: 0,
+            time: 0.0,
+            combo: 0,
+            max_combo: 0,
+            since_kill: 0.0,
+        }
+    }
+
+    pub fn shot(&mut self) {
+        self.shots += 1;
+    }
+
+    pub fn hit(&mut self) {
+        self.hits += 1;
+    }
+
+    pub fn kill(&mut self, kind: usize, points: u32) {
+        if kind >= self.kills.len() {
+            self.kills.resize(kind + 1, 0);
+            self.points.resize(kind + 1, 0);
+        }
+        self.kills[kind] += 1;
+        self.points[kind] += points;
+        self.combo += 1;
+        self.max_combo = self.max_combo.max(self.combo);
+        self.since_kill = 0.0;
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.time += delta_time;
+        self.since_kill += delta_time;
+        if self.since_kill > COMBO_WINDOW {
+            self.combo = 0;
+        }
+    }
+
+    //share of the combo window left before the combo drops
+    pub fn combo_left(&self) -> f32 {
+        if self.combo == 0 {
+            return 0.0;
+        }
+        (1.0 - self.since_kill / COMBO_WINDOW).clamp(0.0, 1.0)
+    }
+
+    pub fn total_kills(&self) -> u32 {
+        self.kills.iter().sum()
+    }
+}
+
+//0-100, nothing fired counts as 0
+pub fn accuracy(shots: u32, hits: u32) -> u32 {
+    (hits * 100).checked_div(shots).unwrap_or(0)
+}
+
+//`m:ss`
+pub fn format_time(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+//best score for each difficulty, as `difficulty = score` lines
+#[derive(Default)]
+pub struct HighScores {
+    scores: Vec<(String, u32)>,
+}
+
+impl HighScores {
+    pub fn load() -> Self {
+        let Ok(text) = fs::read_to_string(HIGH_SCORE_PATH) else {
+            return HighScores::default();
+        };
+        //the file used to hold a lone number, set before there was a choice
+        if let Ok(score) = text.trim().parse() {
+            return HighScores {
+                scores: vec![("normal".to_string(), score)],
+            };
+        }
+        HighScores {
+            scores: Config::parse(&text)
+                .root()
+                .entries
+                .iter()
+                .filter_map(|(key, value)| Some((key.clone(), value.parse().ok()?)))
+                .collect(),
+        }
+    }
+
+    pub fn get(&self, difficulty: &str) -> u32 {
+        self.scores
+            .iter()
+            .find(|(key, _)| key == difficulty)
+            .map_or(0, |(_, score)| *score)
+    }
+
+    pub fn set(&mut self, difficulty: &str, score: u32) {
+        match self.scores.iter_mut().find(|(key, _)| key == difficulty) {
+            Some((_, best)) => *best = score,
+            None => self.scores.push((difficulty.to_string(), score)),
+        }
+    }
+
+    pub fn save(&self) {
+        let text: String = self
+            .scores
+            .iter()
+            .map(|(key, score)| format!("{} = {}\n", key, score))
+            .collect();
+        fs::write(HIGH_SCORE_PATH, text).ok();
+    }
+}
+
+//totals over every run, kept next to the high score
+#[derive(Default)]
+pub struct Lifetime {
+    pub runs: u32,
+    pub kills: u32,
+    pub shots: u32,
+    pub hits: u32,
+    pub time: f32,
+    pub best_combo: u32,
+}
+
+impl Lifetime {
+    pub fn load() -> Self {
+        let Some(config) = Config::load(LIFETIME_PATH) else {
+            return Lifetime::default();
+        };
+        let root = config.root();
+        Lifetime {
+            runs: root.get_u32("runs", 0),
+            kills: root.get_u32("kills", 0),
+            shots: root.get_u32("shots", 0),
+            hits: root.get_u32("hits", 0),
+            time: root.get_f32("time", 0.0),
+            best_combo: root.get_u32("best_combo", 0),
+        }
+    }
+
+    pub fn add(&mut self, run: &RunStats) {
+        self.runs += 1;
+        self.kills += run.total_kills();
+        self.shots += run.shots;
+        self.hits += run.hits;
+        self.time += run.time;
+        self.best_combo = self.best_combo.max(run.max_combo);
+    }
+
+    pub fn save(&self) {
+        let text = format!(
+            "runs = {}\nkills = {}\nshots = {}\nhits = {}\ntime = {}\nbest_combo = {}\n",
+            self.runs, self.kills, self.shots, self.hits, self.time, self.best_combo
+        );
+        fs::write(LIFETIME_PATH, text).ok();
+    }
+}
@@ -0,0 +1,74 @@
+use macroquad::prelude::*;
+
+use crate::postfx::PostFx;
+
+//the playfield is always WIDTH x HEIGHT units, whatever the window size. it
+//is rendered into an offscreen target at half that (sprites are drawn at 2x,
+//so one art pixel lands on one target pixel) and scaled up by whole numbers,
+//letterboxed inside the window
+pub const WIDTH: f32 = 1280.0;
+pub const HEIGHT: f32 = 720.0;
+const PIXELS_PER_UNIT: f32 = 0.5;
+
+pub struct Viewport {
+    render_target: RenderTarget,
+}
+
+impl Viewport {
+    pub fn new() -> Self {
+        let render_target = render_target(
+            (WIDTH * PIXELS_PER_UNIT) as u32,
+            (HEIGHT * PIXELS_PER_UNIT) as u32,
+        );
+        render_target.texture.set_filter(FilterMode::Nearest);
+
+        Viewport { render_target }
+    }
+
+    //everything drawn until `end` is in playfield units, `offset` and
+    //`rotation` (degrees) move the view for screen shake
+    pub fn begin(&self, offset: Vec2, rotation: f32) {
+        let mut camera =
+            Camera2D::from_display_rect(Rect::new(offset.x, offset.y, WIDTH, HEIGHT));
+        camera.rotation = rotation;
+        camera.render_target = Some(self.render_target.clone());
+        set_camera(&camera);
+        clear_background(BLACK);
+    }
+
+    //back to screen space, with the playfield drawn letterboxed through the
+    //post processing chain
+    pub fn end(&self, postfx: &PostFx) {
+        set_default_camera();
+        postfx.draw(&self.render_target.texture, Self::screen_rect());
+    }
+
+    //size of the offscreen target in pixels
+    pub fn pixel_size(&self) -> Vec2 {
+        self.render_target.texture.size()
+    }
+
+    //where the playfield lands in the window; windows smaller than the
+    //target fall back to a fractional scale rather than cropping
+    pub fn screen_rect() -> Rect {
+        let pixels = vec2(WIDTH, HEIGHT) * PIXELS_PER_UNIT;
+        let fit = (screen_width() / pixels.x).min(screen_height() / pixels.y);
+        let scale = if fit >= 1.0 { fit.floor() } else { fit };
+        let size = pixels * scale;
+        Rect::new(
+            ((screen_width() - size.x) / 2.0).floor(),
+            ((screen_height() - size.y) / 2.0).floor(),
+            size.x,
+            size.y,
+        )
+    }
+
+    pub fn screen_to_world(position: Vec2) -> Vec2 {
+        let rect = Self::screen_rect();
+        (position - rect.point()) / rect.size() * vec2(WIDTH, HEIGHT)
+    }
+
+    pub fn mouse_position() -> Vec2 {
+        Self::screen_to_world(mouse_position().into())
+    }
+}
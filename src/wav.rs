@@ -0,0 +1,214 @@
+//minimal 16-bit PCM wav reader/writer so sound effects can be baked into
+//variants at load time (macroquad's audio has no pitch control of its own)
+
+pub struct Wav {
+    pub channels: u16,
+    pub sample_rate: u32,
+    //interleaved samples
+    pub samples: Vec<i16>,
+}
+
+struct Format {
+    tag: u16,
+    channels: u16,
+    sample_rate: u32,
+    bits: u16,
+}
+
+//the `fmt ` and `data` chunks of a RIFF/WAVE file
+fn chunks(bytes: &[u8]) -> Result<(Format, &[u8]), String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("not a RIFF/WAVE file".to_string());
+    }
+
+    let mut format = None;
+    let mut data = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let len = u32::from_le_bytes([bytes[pos + 4], bytes[pos + 5], bytes[pos + 6], bytes[pos + 7]]) as usize;
+        let Some(body) = bytes.get(pos + 8..pos + 8 + len) else {
+            return Err(format!("`{}` chunk runs past the end", String::from_utf8_lossy(id)));
+        };
+        match id {
+            b"fmt " if len >= 16 => {
+                format = Some(Format {
+                    tag: u16::from_le_bytes([body[0], body[1]]),
+                    channels: u16::from_le_bytes([body[2], body[3]]),
+                    sample_rate: u32::from_le_bytes([body[4], body[5], body[6], body[7]]),
+                    bits: u16::from_le_bytes([body[14], body[15]]),
+                });
+            }
+            b"data" => data = Some(body),
+            _ => {}
+        }
+        //chunks are padded to an even length
+        pos += 8 + len + (len & 1);
+    }
+
+    match (format, data) {
+        (None, _) => Err("no `fmt ` chunk".to_string()),
+        (_, None) => Err("no `data` chunk".to_string()),
+        (Some(format), Some(data)) => Ok((format, data)),
+    }
+}
+
+impl Wav {
+    //whether macroquad's audio can play the file: pcm or float samples,
+    //mono or stereo. only 16-bit pcm can be `parse`d for baking variants
+    pub fn check(bytes: &[u8]) -> Result<(), String> {
+        let (format, data) = chunks(bytes)?;
+        if !matches!(format.tag, 1 | 3 | 0xfffe) {
+            return Err(format!("unsupported wav encoding {}", format.tag));
+        }
+        if !matches!(format.channels, 1 | 2) {
+            return Err(format!("{} channels, only mono or stereo play", format.channels));
+        }
+        if format.sample_rate == 0 || data.is_empty() {
+            return Err("no samples".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn parse(bytes: &[u8]) -> Option<Wav> {
+        let (format, data) = chunks(bytes).ok()?;
+        if format.tag != 1 || format.bits != 16 || format.channels == 0 || data.len() < 2 {
+            return None;
+        }
+        let samples = data
+            .chunks_exact(2)
+            .map(|s| i16::from_le_bytes([s[0], s[1]]))
+            .collect();
+
+        Some(Wav {
+            channels: format.channels,
+            sample_rate: format.sample_rate,
+            samples,
+        })
+    }
+
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels as usize
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.frames() as f32 / self.sample_rate as f32
+    }
+
+    //linear resample, pitch > 1.0 plays higher and shorter
+    pub fn resampled(&self, pitch: f32) -> Wav {
+        let channels = self.channels as usize;
+        let frames = self.frames();
+        let new_frames = (frames as f32 / pitch) as usize;
+        let mut samples = Vec::with_capacity(new_frames * channels);
+        for i in 0..new_frames {
+            let src = i as f32 * pitch;
+            let a = (src as usize).min(frames - 1);
+            let b = (a + 1).min(frames - 1);
+            let t = src.fract();
+            for c in 0..channels {
+                let sa = self.samples[a * channels + c] as f32;
+                let sb = self.samples[b * channels + c] as f32;
+                samples.push((sa + (sb - sa) * t) as i16);
+            }
+        }
+
+        Wav {
+            channels: self.channels,
+            sample_rate: self.sample_rate,
+            samples,
+        }
+    }
+
+    //stereo copy balanced between -1.0 (left) and 1.0 (right); the near
+    //side stays at full volume so the center matches the original mix
+    pub fn panned(&self, pan: f32) -> Wav {
+        let channels = self.channels as usize;
+        let left = (1.0 - pan).min(1.0);
+        let right = (1.0 + pan).min(1.0);
+        let mut samples = Vec::with_capacity(self.frames() * 2);
+        for frame in self.samples.chunks_exact(channels) {
+            let l = frame[0] as f32;
+            let r = frame[channels.min(2) - 1] as f32;
+            samples.push((l * left) as i16);
+            samples.push((r * right) as i16);
+        }
+
+        Wav {
+            channels: 2,
+            sample_rate: self.sample_rate,
+            samples,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let data_len = self.samples.len() as u32 * 2;
+        let block_align = self.channels * 2;
+        let mut bytes = Vec::with_capacity(44 + data_len as usize);
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&self.channels.to_le_bytes());
+        bytes.extend_from_slice(&self.sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(self.sample_rate * block_align as u32).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for sample in &self.samples {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let wav = Wav {
+            channels: 2,
+            sample_rate: 22050,
+            samples: vec![0, 1, -1, i16::MAX, i16::MIN, 1234],
+        };
+        let bytes = wav.to_bytes();
+        assert!(Wav::check(&bytes).is_ok());
+        let parsed = Wav::parse(&bytes).unwrap();
+        assert_eq!(parsed.channels, 2);
+        assert_eq!(parsed.sample_rate, 22050);
+        assert_eq!(parsed.samples, wav.samples);
+        assert_eq!(parsed.frames(), 3);
+    }
+
+    #[test]
+    fn rejects_broken_files() {
+        let mut bytes = Wav {
+            channels: 1,
+            sample_rate: 44100,
+            samples: vec![0; 16],
+        }
+        .to_bytes();
+        assert!(Wav::check(b"OggS").is_err());
+        assert!(Wav::check(&bytes[..40]).is_err());
+        //claims six channels
+        bytes[22] = 6;
+        assert!(Wav::check(&bytes).is_err());
+    }
+
+    #[test]
+    fn panning_keeps_the_near_side() {
+        let wav = Wav {
+            channels: 1,
+            sample_rate: 44100,
+            samples: vec![1000, -1000],
+        };
+        let left = wav.panned(-1.0);
+        assert_eq!(left.channels, 2);
+        assert_eq!(left.samples, [1000, 0, -1000, 0]);
+        assert_eq!(wav.resampled(2.0).frames(), 1);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::config::Config;
+
+pub struct WeaponDef {
+    pub name: String,
+    //seconds between shots
+    pub cooldown: f32,
+    //bullet speed as a multiple of the ship's
+    pub speed: f32,
+    pub sound: String,
+    //particle effects at the ship when firing and where a bullet hits
+    pub muzzle: Option<String>,
+    pub hit: Option<String>,
+}
+
+impl Default for WeaponDef {
+    fn default() -> Self {
+        WeaponDef {
+            name: "laser".to_string(),
+            cooldown: 0.5,
+            speed: 2.0,
+            sound: "laser".to_string(),
+            muzzle: None,
+            hit: None,
+        }
+    }
+}
+
+//`[weapon <name>]` sections, the first is what the ship starts with
+pub fn load(text: &str) -> Vec<WeaponDef> {
+    let config = Config::parse(text);
+    let defaults = WeaponDef::default();
+    let mut weapons: Vec<WeaponDef> = config
+        .sections
+        .iter()
+        .filter_map(|section| Some((section.name.strip_prefix("weapon ")?, section)))
+        .map(|(name, section)| WeaponDef {
+            name: name.to_string(),
+            cooldown: section.get_f32("cooldown", defaults.cooldown),
+            speed: section.get_f32("speed", defaults.speed),
+            sound: section.get("sound").unwrap_or(&defaults.sound).to_string(),
+            muzzle: section.get("muzzle").map(str::to_string),
+            hit: section.get("hit").map(str::to_string),
+        })
+        .collect();
+
+    if weapons.is_empty() {
+        weapons.push(defaults);
+    }
+    weapons
+}
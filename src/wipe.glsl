@@ -0,0 +1,34 @@
+#version 100
+precision highp float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 source_size;
+uniform float progress;
+uniform float style;
+
+float hash(vec2 p) {
+    return fract(sin(dot(p, vec2(12.9898, 78.233))) * 43758.5453);
+}
+
+void main() {
+    // 0.0 is the untouched screen, 1.0 fully covered
+    float cover = progress;
+    vec3 col = vec3(0.);
+    if (style > 2.5) {
+        // warp, the starfield rushes in over everything
+        col = texture2D(Texture, uv).rgb;
+    } else if (style > 1.5) {
+        // iris, a circle closing on the centre, stretched to stay round
+        vec2 aspect = vec2(source_size.x / source_size.y, 1.);
+        float radius = (1. - progress) * length(aspect) * .5;
+        float edge = .01 * (1. - progress);
+        cover = smoothstep(radius - edge, radius + edge, length((uv - .5) * aspect));
+    } else if (style > .5) {
+        // dissolve, blocks of the playfield drop out in a random order
+        vec2 cell = floor(uv * vec2(64., 36.));
+        cover = step(hash(cell), progress);
+    }
+    gl_FragColor = vec4(col, cover);
+}
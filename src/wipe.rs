@@ -0,0 +1,163 @@
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui, widgets, Skin};
+
+use crate::shader::{self, ShaderFile, POSTFX_VERTEX, WIPE_FRAGMENT};
+use crate::skin;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WipeStyle {
+    Fade,
+    Dissolve,
+    Iris,
+    //the starfield rushes over the screen while the stars speed up
+    Warp,
+}
+
+//covers the screen between two states: the first half closes over the old
+//state, the switch happens fully covered, the second half opens on the new one.
+//the ui windows are drawn after everything else, so the wipe is rendered into
+//a target and shown in a window of its own kept above them
+pub struct Wipe {
+    //left empty when the shader fails to compile, every style falls back to a fade
+    material: Option<Material>,
+    //window sized, remade when the window is resized
+    target: Option<RenderTarget>,
+    skin: Skin,
+    style: WipeStyle,
+    time: f32,
+    duration: f32,
+}
+
+fn compile() -> Result<Material, String> {
+    shader::compile(
+        &POSTFX_VERTEX,
+        &WIPE_FRAGMENT,
+        MaterialParams {
+            uniforms: vec![
+                UniformDesc::new("source_size", UniformType::Float2),
+                UniformDesc::new("progress", UniformType::Float1),
+                UniformDesc::new("style", UniformType::Float1),
+            ],
+            //written to the target as is, the ui blends it over the screen
+            ..Default::default()
+        },
+    )
+}
+
+impl Wipe {
+    pub fn new() -> Self {
+        Wipe {
+            material: compile()
+                .inspect_err(|e| error!("{}: {}", WIPE_FRAGMENT.path, e))
+                .ok(),
+            target: None,
+            skin: skin::build_cover(),
+            style: WipeStyle::Fade,
+            time: 0.0,
+            duration: 0.0,
+        }
+    }
+
+    //the vertex shader is shared with, and watched for, the post processing
+    pub fn shader_files(&self) -> [&'static ShaderFile; 1] {
+        [&WIPE_FRAGMENT]
+    }
+
+    pub fn owns(&self, path: &str) -> bool {
+        path == WIPE_FRAGMENT.path
+    }
+
+    //keeps the previous material if the new source doesn't compile
+    pub fn reload(&mut self) -> Result<(), String> {
+        self.material = Some(compile()?);
+        Ok(())
+    }
+
+    pub fn start(&mut self, style: WipeStyle, seconds: f32) {
+        self.style = style;
+        self.time = 0.0;
+        self.duration = seconds.max(0.0);
+    }
+
+    pub fn active(&self) -> bool {
+        self.time < self.duration
+    }
+
+    //true on the frame the screen becomes fully covered
+    pub fn update(&mut self, delta_time: f32) -> bool {
+        if !self.active() {
+            return false;
+        }
+        let half = self.duration / 2.0;
+        let was_open = self.time < half;
+        self.time += delta_time;
+        was_open && self.time >= half
+    }
+
+    //0.0 open, 1.0 fully covered
+    fn cover(&self) -> f32 {
+        let half = self.duration / 2.0;
+        (1.0 - (self.time - half).abs() / half).clamp(0.0, 1.0)
+    }
+
+    //over `dest` in window space, `stars` is the starfield for the warp
+    pub fn draw(&mut self, dest: Rect, stars: &Texture2D) {
+        if !self.active() {
+            return;
+        }
+        let cover = self.material.clone().map(|material| self.render(&material, dest.size(), stars));
+
+        let mut ui = root_ui();
+        ui.push_skin(&self.skin);
+        let id = hash!();
+        widgets::Window::new(id, dest.point(), dest.size())
+            .titlebar(false)
+            .movable(false)
+            .ui(&mut ui, |ui| match &cover {
+                Some(texture) => ui.canvas().image(dest, texture),
+                None => ui.canvas().rect(dest, None, Color::new(0.0, 0.0, 0.0, self.cover())),
+            });
+        //on top of the windows of the states, and so also takes their mouse input
+        ui.focus_window(id);
+        ui.pop_skin();
+    }
+
+    fn render(&mut self, material: &Material, size: Vec2, stars: &Texture2D) -> Texture2D {
+        let (width, height) = (size.x as u32, size.y as u32);
+        let target = match &self.target {
+            Some(target) if target.texture.size() == vec2(width as f32, height as f32) => target.clone(),
+            _ => {
+                let target = render_target(width, height);
+                self.target = Some(target.clone());
+                target
+            }
+        };
+        //upside down, as the ui doesn't flip render targets the way
+        //`draw_texture_ex` can
+        set_camera(&Camera2D {
+            target: size / 2.0,
+            zoom: vec2(2.0 / size.x, 2.0 / size.y),
+            render_target: Some(target.clone()),
+            ..Default::default()
+        });
+        clear_background(Color::new(0.0, 0.0, 0.0, 0.0));
+        material.set_uniform("source_size", size);
+        material.set_uniform("progress", self.cover());
+        material.set_uniform("style", self.style as u8 as f32);
+        gl_use_material(material);
+        draw_texture_ex(
+            stars,
+            0.0,
+            0.0,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(size),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+        gl_use_default_material();
+        set_default_camera();
+        target.texture
+    }
+}